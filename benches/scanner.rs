@@ -0,0 +1,65 @@
+use std::time::Instant;
+
+use cool_compiler_rs::Scanner;
+
+/*
+scanner throughput on generated input of growing size
+
+cargo bench --bench scanner
+
+the time per byte should stay flat as the input doubles , the run fails if the largest input
+costs more than three times as much per byte as the smallest
+*/
+
+const CLASS:&str = "(* a generated class , (* with a nested comment *) *)
+class Counter inherits IO {
+    count : Int <- 0;
+    name : String <- \"counter\\tone\\n\";
+    -- a line comment
+    step(by : Int) : SELF_TYPE {
+        {
+            count <- count + by * 2 - 1;
+            if count <= 100 then out_string(name) else out_int(count) fi;
+            self;
+        }
+    };
+};
+";
+
+fn source(size:usize)->String{
+    let mut source = String::with_capacity(size + CLASS.len());
+    while source.len() < size{
+        source.push_str(CLASS);
+    }
+    source
+}
+
+//best of a few runs , in nanoseconds per byte
+fn measure(source:&str)->f64{
+    (0..5).map(|_|{
+        let mut scanner = Scanner::new(source.to_string());
+        let start = Instant::now();
+        let (tokens,diagnostics) = scanner.scan_all();
+        let elapsed = start.elapsed();
+        assert!(!diagnostics.has_errors());
+        assert!(tokens.len() > 1);
+        elapsed.as_nanos() as f64 / source.len() as f64
+    })
+    .fold(f64::INFINITY,f64::min)
+}
+
+fn main(){
+    let mut costs = vec![];
+    for megabytes in [1,2,4,8]{
+        let source = source(megabytes << 20);
+        let cost = measure(&source);
+        println!("scan {} MiB: {:.2} ns/byte , {:.1} MiB/s",megabytes,cost,1e3 / cost / 1.048576);
+        costs.push(cost);
+    }
+    let ratio = costs[costs.len() - 1] / costs[0];
+    println!("cost per byte at 8 MiB is {:.2}x the cost at 1 MiB",ratio);
+    if ratio > 3.0{
+        eprintln!("scanning is not linear in the size of the input");
+        std::process::exit(1);
+    }
+}
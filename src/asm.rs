@@ -0,0 +1,749 @@
+use std::collections::HashMap;
+
+use crate::ast::*;
+use crate::semant::*;
+use crate::token::{Token,TokenType};
+
+/*
+mips code generation for the spim simulator , following the standard cool runtime (trap.handler)
+
+object layout
+
+-4  : -1 eye catcher for the garbage collector
+ 0  : class tag
+ 4  : size of the object in words
+ 8  : dispatch table
+12  : attributes , inherited ones first
+
+Int and Bool keep their raw value in the first attribute slot . String keeps a pointer to an Int
+holding its length followed by the null terminated characters.
+
+calling convention
+
+the caller pushes the arguments from left to right , puts the receiver in $a0 and jumps to the method.
+the callee saves $fp , $s0 and $ra , keeps self in $s0 and pops the arguments before it returns.
+$fp points at the first local of the frame:
+
+    arguments   fp + 4*locals + 12 ...  (the last argument is the nearest)
+    old $fp     fp + 4*locals + 8
+    old $s0     fp + 4*locals + 4
+    $ra         fp + 4*locals
+    locals      fp + 0 ...              (let and case variables)
+
+every expression leaves its value in $a0 . intermediate values are pushed on the stack , which only
+ever holds object pointers so the garbage collector can scan it.
+*/
+
+const WORD:usize = 4;
+const HEADER:usize = 3;//tag , size , dispatch table
+
+#[derive(Clone,Copy)]
+enum Location{
+    Frame(usize),//byte offset from $fp
+    Attribute(usize)//byte offset from $s0
+}
+
+pub struct CodeGen<'a>{
+    table : &'a ClassTable,
+    types : &'a TypeMap,
+    filename : String,//file of the class being generated , for runtime error messages
+    gc : bool,
+    tags : HashMap<String,usize>,
+    max_tags : HashMap<String,usize>,//largest tag in the subtree of each class , for case
+    dispatch : HashMap<String,Vec<(String,String)>>,//class -> (defining class,method) in table order
+    strings : Vec<String>,
+    ints : Vec<i32>,
+    text : String,
+    labels : usize,
+    class : String,
+    scope : Vec<(String,Location)>,
+    frame_locals : usize,//locals reserved by the current frame
+    next_local : usize
+}
+
+pub fn generate(program:&Program,analysis:&Analysis,gc:bool)->String{
+    let mut codegen = CodeGen::new(&analysis.table,&analysis.types,gc);
+    program.accept(&mut codegen);
+    codegen.finish()
+}
+
+impl<'a> CodeGen<'a>{
+
+    pub fn new(table:&'a ClassTable,types:&'a TypeMap,gc:bool)->CodeGen<'a>{
+        let mut codegen = CodeGen{
+            table,
+            types,
+            filename:String::new(),
+            gc,
+            tags:HashMap::new(),
+            max_tags:HashMap::new(),
+            dispatch:HashMap::new(),
+            strings:vec![],
+            ints:vec![],
+            text:String::new(),
+            labels:0,
+            class:OBJECT.to_string(),
+            scope:vec![],
+            frame_locals:0,
+            next_local:0
+        };
+        for (tag,class) in table.order().iter().enumerate(){
+            codegen.tags.insert(class.clone(),tag);
+            for ancestor in table.ancestors(class){
+                codegen.max_tags.insert(ancestor.to_string(),tag);
+            }
+            let mut methods = match table.get(class).and_then(|c| c.parent.as_ref()){
+                Some(parent) => codegen.dispatch[parent].clone(),
+                None => vec![]
+            };
+            for method in table.get(class).unwrap().methods.iter(){
+                match methods.iter().position(|(_,m)| m == &method.name){
+                    Some(index) => methods[index] = (class.clone(),method.name.clone()),
+                    None => methods.push((class.clone(),method.name.clone()))
+                }
+            }
+            codegen.dispatch.insert(class.clone(),methods);
+            codegen.string_const(class);
+        }
+        for class in table.order().iter(){
+            let filename = table.get(class).unwrap().filename.clone();
+            if !filename.is_empty(){
+                codegen.string_const(&filename);
+            }
+        }
+        codegen.string_const("");
+        codegen.int_const(0);
+        codegen
+    }
+
+    fn emit(&mut self,instruction:&str){
+        self.text.push('\t');
+        self.text.push_str(instruction);
+        self.text.push('\n');
+    }
+
+    fn label(&mut self,label:&str){
+        self.text.push_str(label);
+        self.text.push_str(":\n");
+    }
+
+    fn new_label(&mut self)->String{
+        self.labels += 1;
+        format!("label{}",self.labels)
+    }
+
+    fn push(&mut self,register:&str){
+        self.emit(&format!("sw\t{} 0($sp)",register));
+        self.emit("addiu\t$sp $sp -4");
+    }
+
+    fn pop(&mut self,register:&str){
+        self.emit(&format!("lw\t{} 4($sp)",register));
+        self.emit("addiu\t$sp $sp 4");
+    }
+
+    fn string_const(&mut self,value:&str)->String{
+        let index = match self.strings.iter().position(|s| s == value){
+            Some(index) => index,
+            None =>{
+                self.strings.push(value.to_string());
+                self.strings.len() - 1
+            }
+        };
+        format!("str_const{}",index)
+    }
+
+    fn int_const(&mut self,value:i32)->String{
+        let index = match self.ints.iter().position(|i| *i == value){
+            Some(index) => index,
+            None =>{
+                self.ints.push(value);
+                self.ints.len() - 1
+            }
+        };
+        format!("int_const{}",index)
+    }
+
+    fn tag(&self,class:&str)->usize{
+        self.tags[class]
+    }
+
+    fn resolve(&self,type_:&str)->String{
+        if type_ == SELF_TYPE {self.class.clone()} else {type_.to_string()}
+    }
+
+    fn attribute_offset(&self,class:&str,name:&str)->Option<usize>{
+        self.table.all_attributes(class).iter()
+            .position(|a| a.name == name)
+            .map(|index| (HEADER + index) * WORD)
+    }
+
+    fn method_offset(&self,class:&str,method:&str)->usize{
+        self.dispatch[class].iter().position(|(_,m)| m == method).unwrap() * WORD
+    }
+
+    fn lookup(&self,id:&str)->Location{
+        match self.scope.iter().rev().find(|(name,_)| name == id){
+            Some((_,location)) => *location,
+            None => Location::Attribute(self.attribute_offset(&self.class,id).unwrap())
+        }
+    }
+
+    //default value of an attribute or let variable that has no initializer
+    fn default_value(&mut self,type_:&str){
+        match type_{
+            INT =>{
+                let label = self.int_const(0);
+                self.emit(&format!("la\t$a0 {}",label));
+            }
+            STRING =>{
+                let label = self.string_const("");
+                self.emit(&format!("la\t$a0 {}",label));
+            }
+            BOOL => self.emit("la\t$a0 bool_const0"),
+            _ => self.emit("move\t$a0 $zero")
+        }
+    }
+
+    fn store(&mut self,location:Location){
+        match location{
+            Location::Frame(offset) => self.emit(&format!("sw\t$a0 {}($fp)",offset)),
+            Location::Attribute(offset) =>{
+                self.emit(&format!("sw\t$a0 {}($s0)",offset));
+                if self.gc{
+                    self.emit(&format!("addiu\t$a1 $s0 {}",offset));
+                    self.emit("jal\t_GenGC_Assign");
+                }
+            }
+        }
+    }
+
+    //reserves the next local slot of the frame for a let or case variable
+    fn bind_local(&mut self,id:&str)->Location{
+        let location = Location::Frame(self.next_local * WORD);
+        self.next_local += 1;
+        self.scope.push((id.to_string(),location));
+        location
+    }
+
+    fn unbind_local(&mut self){
+        self.scope.pop();
+        self.next_local -= 1;
+    }
+
+    fn prologue(&mut self,locals:usize){
+        let frame = (HEADER + locals) * WORD;
+        self.emit(&format!("addiu\t$sp $sp -{}",frame));
+        self.emit(&format!("sw\t$fp {}($sp)",frame));
+        self.emit(&format!("sw\t$s0 {}($sp)",frame - WORD));
+        self.emit(&format!("sw\t$ra {}($sp)",frame - 2 * WORD));
+        self.emit("addiu\t$fp $sp 4");
+        self.emit("move\t$s0 $a0");
+        self.frame_locals = locals;
+        self.next_local = 0;
+    }
+
+    fn epilogue(&mut self,arguments:usize){
+        let frame = (HEADER + self.frame_locals) * WORD;
+        self.emit(&format!("lw\t$fp {}($sp)",frame));
+        self.emit(&format!("lw\t$s0 {}($sp)",frame - WORD));
+        self.emit(&format!("lw\t$ra {}($sp)",frame - 2 * WORD));
+        self.emit(&format!("addiu\t$sp $sp {}",frame + arguments * WORD));
+        self.emit("jr\t$ra");
+    }
+
+    fn init_method(&mut self,class:&str,attributes:&[&Feature]){
+        self.class = class.to_string();
+        self.label(&format!("{}_init",class));
+        let locals = attributes.iter().map(|a| match a{
+            Feature::Attribute{expr:Some(expr),..} => locals_needed(expr),
+            _ => 0
+        }).max().unwrap_or(0);
+        self.prologue(locals);
+        if let Some(parent) = self.table.get(class).and_then(|c| c.parent.clone()){
+            self.emit(&format!("jal\t{}_init",parent));
+        }
+        for attribute in attributes{
+            attribute.accept(self);
+        }
+        self.emit("move\t$a0 $s0");
+        self.epilogue(0);
+    }
+
+    fn finish(self)->String{
+        let mut data = String::new();
+        let mut emit = |line:String| {data.push_str(&line);data.push('\n');};
+        emit("\t.data\n\t.align\t2".to_string());
+        for global in ["class_nameTab","Main_protObj","Int_protObj","String_protObj","bool_const0","bool_const1","_int_tag","_bool_tag","_string_tag"]{
+            emit(format!("\t.globl\t{}",global));
+        }
+        for (label,class) in [("_int_tag",INT),("_bool_tag",BOOL),("_string_tag",STRING)]{
+            emit(format!("{}:\n\t.word\t{}",label,self.tags[class]));
+        }
+        let (initializer,collector) = if self.gc {("_GenGC_Init","_GenGC_Collect")} else {("_NoGC_Init","_NoGC_Collect")};
+        emit(format!("\t.globl\t_MemMgr_INITIALIZER\n_MemMgr_INITIALIZER:\n\t.word\t{}",initializer));
+        emit(format!("\t.globl\t_MemMgr_COLLECTOR\n_MemMgr_COLLECTOR:\n\t.word\t{}",collector));
+        emit("\t.globl\t_MemMgr_TEST\n_MemMgr_TEST:\n\t.word\t0".to_string());
+
+        //string lengths need int constants , so strings are laid out before the ints
+        let mut ints = self.ints.clone();
+        for (index,value) in self.strings.iter().enumerate(){
+            let length = value.len() as i32;
+            let length_index = match ints.iter().position(|i| *i == length){
+                Some(index) => index,
+                None =>{ints.push(length);ints.len() - 1}
+            };
+            emit("\t.word\t-1".to_string());
+            emit(format!("str_const{}:",index));
+            emit(format!("\t.word\t{}",self.tags[STRING]));
+            emit(format!("\t.word\t{}",HEADER + 1 + (value.len() + WORD) / WORD));
+            emit("\t.word\tString_dispTab".to_string());
+            emit(format!("\t.word\tint_const{}",length_index));
+            emit(ascii(value));
+            emit("\t.byte\t0\n\t.align\t2".to_string());
+        }
+        for (index,value) in ints.iter().enumerate(){
+            emit(format!("\t.word\t-1\nint_const{}:",index));
+            emit(format!("\t.word\t{}\n\t.word\t4\n\t.word\tInt_dispTab\n\t.word\t{}",self.tags[INT],value));
+        }
+        for value in 0..2{
+            emit(format!("\t.word\t-1\nbool_const{}:",value));
+            emit(format!("\t.word\t{}\n\t.word\t4\n\t.word\tBool_dispTab\n\t.word\t{}",self.tags[BOOL],value));
+        }
+
+        let order = self.table.order();
+        emit("class_nameTab:".to_string());
+        for class in order{
+            let index = self.strings.iter().position(|s| s == class).unwrap();
+            emit(format!("\t.word\tstr_const{}",index));
+        }
+        emit("class_objTab:".to_string());
+        for class in order{
+            emit(format!("\t.word\t{}_protObj\n\t.word\t{}_init",class,class));
+        }
+        for class in order{
+            emit(format!("{}_dispTab:",class));
+            for (definer,method) in self.dispatch[class].iter(){
+                emit(format!("\t.word\t{}.{}",definer,method));
+            }
+        }
+        let empty_string = self.strings.iter().position(|s| s.is_empty()).unwrap();
+        let zero = ints.iter().position(|i| *i == 0).unwrap();
+        for class in order{
+            let attributes = self.table.all_attributes(class);
+            let mut slots : Vec<String> = match class.as_str(){
+                INT|BOOL => vec!["0".to_string()],
+                STRING => vec![format!("int_const{}",zero),"0".to_string()],
+                _ => vec![]
+            };
+            for attribute in attributes{
+                slots.push(match attribute.type_.as_str(){
+                    INT => format!("int_const{}",zero),
+                    STRING => format!("str_const{}",empty_string),
+                    BOOL => "bool_const0".to_string(),
+                    _ => "0".to_string()
+                });
+            }
+            emit(format!("\t.word\t-1\n{}_protObj:",class));
+            emit(format!("\t.word\t{}\n\t.word\t{}\n\t.word\t{}_dispTab",self.tags[class],HEADER + slots.len(),class));
+            for slot in slots{
+                emit(format!("\t.word\t{}",slot));
+            }
+        }
+        emit("\t.globl\theap_start\nheap_start:\n\t.word\t0".to_string());
+
+        emit("\t.text".to_string());
+        for global in ["Main_init","Int_init","String_init","Bool_init","Main.main"]{
+            emit(format!("\t.globl\t{}",global));
+        }
+        data.push_str(&self.text);
+        data
+    }
+}
+
+//the string as .ascii chunks , characters spim cannot take literally become .byte
+fn ascii(value:&str)->String{
+    let mut lines : Vec<String> = vec![];
+    let mut chunk = String::new();
+    for byte in value.bytes(){
+        if (b' '..=b'~').contains(&byte) && byte != b'"' && byte != b'\\'{
+            chunk.push(byte as char);
+            continue;
+        }
+        if !chunk.is_empty(){
+            lines.push(format!("\t.ascii\t\"{}\"",chunk));
+            chunk.clear();
+        }
+        lines.push(format!("\t.byte\t{}",byte));
+    }
+    if !chunk.is_empty() || lines.is_empty(){
+        lines.push(format!("\t.ascii\t\"{}\"",chunk));
+    }
+    lines.join("\n")
+}
+
+//how many let and case variables are alive at the same time while evaluating the expression
+fn locals_needed(expr:&Expr)->usize{
+    match expr{
+        Expr::Let{declarations,body,..} =>{
+            let mut needed = declarations.len() + locals_needed(body);
+            for (index,declaration) in declarations.iter().enumerate(){
+                if let Expr::Declaration{expr,..} = declaration{
+                    if let Some(init) = expr.as_ref(){
+                        needed = needed.max(index + locals_needed(init));
+                    }
+                }
+            }
+            needed
+        }
+        Expr::Case{expr,branches,..} =>{
+            let branches = branches.iter().map(|b| match b{
+                Expr::Branch{expr,..} => 1 + locals_needed(expr),
+                _ => 0
+            }).max().unwrap_or(0);
+            branches.max(locals_needed(expr))
+        }
+        Expr::Assign{left,right}|Expr::Comparison{left,right,..}|Expr::Arithmetic{left,right,..}|Expr::Factor{left,right,..} =>
+            locals_needed(left).max(locals_needed(right)),
+        Expr::Not{expr,..}|Expr::Delete{expr,..}|Expr::IsVoid{expr,..}|Expr::BitWiseNot{expr,..}|Expr::Grouping{expr,..}|Expr::Branch{expr,..} =>
+            locals_needed(expr),
+        Expr::Dispatch{expr,arguments,..} =>
+            arguments.iter().map(locals_needed).max().unwrap_or(0).max(locals_needed(expr)),
+        Expr::If{Condition,Then,Else,..} =>
+            locals_needed(Condition).max(locals_needed(Then)).max(locals_needed(Else)),
+        Expr::While{Condition,Loop,..} => locals_needed(Condition).max(locals_needed(Loop)),
+        Expr::Block{exprs,..} => exprs.iter().map(locals_needed).max().unwrap_or(0),
+        Expr::Declaration{expr,..} => expr.as_ref().as_ref().map_or(0,locals_needed),
+        _ => 0
+    }
+}
+
+impl Visitor<()> for CodeGen<'_>{
+
+    fn visit_program(&mut self,classes:&Vec<Class>){
+        let order = self.table.order().clone();
+        for class in order.iter(){
+            if BASIC_CLASSES.contains(&class.as_str()){
+                self.init_method(class,&[]);
+            }
+            else if let Some(definition) = classes.iter().find(|c| &c.type_().lexeme == class){
+                definition.accept(self);
+            }
+        }
+    }
+
+    fn visit_class(&mut self,type_:&Token,_inherits:&Option<Token>,features:&Vec<Feature>){
+        self.filename = self.table.get(&type_.lexeme).unwrap().filename.clone();
+        let attributes : Vec<&Feature> = features.iter().filter(|f| matches!(f,Feature::Attribute{..})).collect();
+        self.init_method(&type_.lexeme,&attributes);
+        for feature in features.iter().filter(|f| matches!(f,Feature::Method{..})){
+            feature.accept(self);
+        }
+    }
+
+    fn visit_attribute(&mut self,id:&Token,_type_:&Token,expr:&Option<Expr>){
+        if let Some(expr) = expr{
+            expr.accept(self);
+            let location = self.lookup(&id.lexeme);
+            self.store(location);
+        }
+    }
+
+    fn visit_method(&mut self,id:&Token,_type_:&Token,parameters:&Vec<Formal>,body:&Expr){
+        self.label(&format!("{}.{}",self.class,id.lexeme));
+        self.prologue(locals_needed(body));
+        let count = parameters.len();
+        for (index,parameter) in parameters.iter().enumerate(){
+            let offset = (self.frame_locals + HEADER + count - 1 - index) * WORD;
+            self.scope.push((parameter.id().lexeme.clone(),Location::Frame(offset)));
+        }
+        body.accept(self);
+        self.scope.clear();
+        self.epilogue(count);
+    }
+
+    fn visit_formal(&mut self,_id:&Token,_type_:&Token){}
+
+    fn visit_expr(&mut self,expr:&Expr){
+        expr.accept(self)
+    }
+
+    fn visit_assign(&mut self,left:&Expr,right:&Expr){
+        right.accept(self);
+        if let Expr::ID{id} = left{
+            let location = self.lookup(&id.lexeme);
+            self.store(location);
+        }
+    }
+
+    fn visit_arithmetic(&mut self,left:&Expr,operator:&Token,right:&Expr){
+        let instruction = if operator.tokentype == TokenType::PLUS {"add"} else {"sub"};
+        self.integer_operation(left,instruction,right);
+    }
+
+    fn visit_factor(&mut self,left:&Expr,operator:&Token,right:&Expr){
+        let instruction = if operator.tokentype == TokenType::STAR {"mul"} else {"div"};
+        self.integer_operation(left,instruction,right);
+    }
+
+    fn visit_comparison(&mut self,left:&Expr,operator:&Token,right:&Expr){
+        left.accept(self);
+        self.push("$a0");
+        right.accept(self);
+        let done = self.new_label();
+        if operator.tokentype == TokenType::EQUALITY{
+            //equality_test compares the values of Int , String and Bool objects
+            self.emit("move\t$t2 $a0");
+            self.pop("$t1");
+            self.emit("la\t$a0 bool_const1");
+            self.emit(&format!("beq\t$t1 $t2 {}",done));
+            self.emit("la\t$a1 bool_const0");
+            self.emit("jal\tequality_test");
+            self.label(&done);
+            return;
+        }
+        let branch = match operator.tokentype{
+            TokenType::LESSTHAN => "blt",
+            TokenType::LESSEQUAL => "ble",
+            TokenType::MORETHAN => "bgt",
+            _ => "bge"
+        };
+        self.pop("$t1");
+        self.emit("lw\t$t1 12($t1)");
+        self.emit("lw\t$t2 12($a0)");
+        self.emit("la\t$a0 bool_const1");
+        self.emit(&format!("{}\t$t1 $t2 {}",branch,done));
+        self.emit("la\t$a0 bool_const0");
+        self.label(&done);
+    }
+
+    fn visit_bitwise_not(&mut self,bitwise_not_expr:&Expr){
+        bitwise_not_expr.accept(self);
+        self.emit("jal\tObject.copy");
+        self.emit("lw\t$t1 12($a0)");
+        self.emit("neg\t$t1 $t1");
+        self.emit("sw\t$t1 12($a0)");
+    }
+
+    fn visit_not(&mut self,not_expr:&Expr){
+        not_expr.accept(self);
+        let done = self.new_label();
+        self.emit("lw\t$t1 12($a0)");
+        self.emit("la\t$a0 bool_const1");
+        self.emit(&format!("beqz\t$t1 {}",done));
+        self.emit("la\t$a0 bool_const0");
+        self.label(&done);
+    }
+
+    fn visit_new(&mut self,new_expr:&Token){
+        if new_expr.lexeme != SELF_TYPE{
+            self.emit(&format!("la\t$a0 {}_protObj",new_expr.lexeme));
+            self.emit("jal\tObject.copy");
+            self.emit(&format!("jal\t{}_init",new_expr.lexeme));
+            return;
+        }
+        //class_objTab holds the prototype and init method of every class , indexed by tag
+        self.emit("la\t$t1 class_objTab");
+        self.emit("lw\t$t2 0($s0)");
+        self.emit("sll\t$t2 $t2 3");
+        self.emit("addu\t$t1 $t1 $t2");
+        self.push("$t1");
+        self.emit("lw\t$a0 0($t1)");
+        self.emit("jal\tObject.copy");
+        self.pop("$t1");
+        self.emit("lw\t$t1 4($t1)");
+        self.emit("jalr\t$t1");
+    }
+
+    //objects are reclaimed by the garbage collector , delete only evaluates its operand
+    fn visit_delete(&mut self,delete_expr:&Expr){
+        delete_expr.accept(self);
+        self.emit("move\t$a0 $zero");
+    }
+
+    fn visit_isvoid(&mut self,isvoid_expr:&Expr){
+        isvoid_expr.accept(self);
+        let done = self.new_label();
+        self.emit("move\t$t1 $a0");
+        self.emit("la\t$a0 bool_const1");
+        self.emit(&format!("beqz\t$t1 {}",done));
+        self.emit("la\t$a0 bool_const0");
+        self.label(&done);
+    }
+
+    fn visit_grouping(&mut self,grouping_expr:&Expr){
+        grouping_expr.accept(self);
+    }
+
+    fn visit_block(&mut self,exprs:&Vec<Expr>){
+        for expr in exprs{
+            expr.accept(self);
+        }
+    }
+
+    fn visit_if(&mut self,condition:&Expr,body:&Expr,else_expr:&Expr){
+        let else_label = self.new_label();
+        let done = self.new_label();
+        condition.accept(self);
+        self.emit("lw\t$t1 12($a0)");
+        self.emit(&format!("beqz\t$t1 {}",else_label));
+        body.accept(self);
+        self.emit(&format!("b\t{}",done));
+        self.label(&else_label);
+        else_expr.accept(self);
+        self.label(&done);
+    }
+
+    fn visit_while(&mut self,condition:&Expr,body:&Expr){
+        let start = self.new_label();
+        let done = self.new_label();
+        self.label(&start);
+        condition.accept(self);
+        self.emit("lw\t$t1 12($a0)");
+        self.emit(&format!("beqz\t$t1 {}",done));
+        body.accept(self);
+        self.emit(&format!("b\t{}",start));
+        self.label(&done);
+        self.emit("move\t$a0 $zero");
+    }
+
+    //branches are tried from the highest tag down , so a subclass is always tried before its ancestors
+    fn visit_case(&mut self,condition:&Expr,branches:&Vec<Expr>){
+        let done = self.new_label();
+        let not_void = self.new_label();
+        condition.accept(self);
+        self.emit(&format!("bnez\t$a0 {}",not_void));
+        let filename = self.string_const(&self.filename.clone());
+        self.emit(&format!("la\t$a0 {}",filename));
+        self.emit(&format!("li\t$t1 {}",condition.line()));
+        self.emit("jal\t_case_abort2");
+        self.label(&not_void);
+        self.emit("lw\t$t2 0($a0)");
+        let mut sorted : Vec<&Expr> = branches.iter().collect();
+        sorted.sort_by_key(|b| match b{
+            Expr::Branch{type_,..} => std::cmp::Reverse(self.tag(&type_.lexeme)),
+            _ => std::cmp::Reverse(0)
+        });
+        for branch in sorted{
+            if let Expr::Branch{type_,..} = branch{
+                let next = self.new_label();
+                self.emit(&format!("blt\t$t2 {} {}",self.tag(&type_.lexeme),next));
+                self.emit(&format!("bgt\t$t2 {} {}",self.max_tags[&type_.lexeme],next));
+                branch.accept(self);
+                self.emit(&format!("b\t{}",done));
+                self.label(&next);
+            }
+        }
+        self.emit("jal\t_case_abort");
+        self.label(&done);
+    }
+
+    fn visit_branch(&mut self,id:&Token,_type_:&Token,expr:&Expr){
+        let location = self.bind_local(&id.lexeme);
+        self.store(location);
+        expr.accept(self);
+        self.unbind_local();
+    }
+
+    fn visit_let(&mut self,declarations:&Vec<Expr>,body:&Expr){
+        for declaration in declarations{
+            declaration.accept(self);
+        }
+        body.accept(self);
+        for _ in declarations{
+            self.unbind_local();
+        }
+    }
+
+    //leaves the variable bound , visit_let unbinds it after the body
+    fn visit_declaration(&mut self,id:&Token,type_:&Token,expr:&Option<Expr>){
+        match expr{
+            Some(expr) => expr.accept(self),
+            None => self.default_value(&type_.lexeme)
+        }
+        let location = self.bind_local(&id.lexeme);
+        self.store(location);
+    }
+
+    fn visit_stringliteral(&mut self,stringliteral:&Token){
+        let value = stringliteral.string_value();
+        let label = self.string_const(value);
+        self.emit(&format!("la\t$a0 {}",label));
+    }
+
+    fn visit_integerliteral(&mut self,integerliteral:&Token){
+        let label = self.int_const(integerliteral.int_value());
+        self.emit(&format!("la\t$a0 {}",label));
+    }
+
+    fn visit_boolliteral(&mut self,boolliteral:&Token){
+        let value = if boolliteral.bool_value() {1} else {0};
+        self.emit(&format!("la\t$a0 bool_const{}",value));
+    }
+
+    fn visit_id(&mut self,id:&Token){
+        if id.lexeme == "self"{
+            self.emit("move\t$a0 $s0");
+            return;
+        }
+        match self.lookup(&id.lexeme){
+            Location::Frame(offset) => self.emit(&format!("lw\t$a0 {}($fp)",offset)),
+            Location::Attribute(offset) => self.emit(&format!("lw\t$a0 {}($s0)",offset))
+        }
+    }
+
+    fn visit_dispatch(&mut self,id:NodeId,target:&Option<Token>,expr:&Expr,method_name:&Option<Token>,arguments:&Vec<Expr>){
+        for argument in arguments{
+            argument.accept(self);
+            self.push("$a0");
+        }
+        let (class,method) = match (method_name,expr){
+            (Some(method),_) =>{
+                expr.accept(self);
+                let static_type = self.types.get(&id).cloned().unwrap_or(OBJECT.to_string());
+                (self.resolve(&static_type),method)
+            }
+            (None,Expr::ID{id}) =>{
+                self.emit("move\t$a0 $s0");
+                (self.class.clone(),id)
+            }
+            (None,_) => return
+        };
+        let not_void = self.new_label();
+        self.emit(&format!("bnez\t$a0 {}",not_void));
+        let filename = self.string_const(&self.filename.clone());
+        self.emit(&format!("la\t$a0 {}",filename));
+        self.emit(&format!("li\t$t1 {}",method.line));
+        self.emit("jal\t_dispatch_abort");
+        self.label(&not_void);
+        let class = match target{
+            Some(target) =>{
+                self.emit(&format!("la\t$t1 {}_dispTab",target.lexeme));
+                target.lexeme.clone()
+            }
+            None =>{
+                self.emit("lw\t$t1 8($a0)");
+                class
+            }
+        };
+        self.emit(&format!("lw\t$t1 {}($t1)",self.method_offset(&class,&method.lexeme)));
+        self.emit("jalr\t$t1");
+    }
+
+    fn not_implemented(&mut self){}
+}
+
+impl CodeGen<'_>{
+    //both operands are Int objects . the result is a fresh copy of the right one holding the new value
+    fn integer_operation(&mut self,left:&Expr,instruction:&str,right:&Expr){
+        left.accept(self);
+        self.push("$a0");
+        right.accept(self);
+        self.emit("jal\tObject.copy");
+        self.pop("$t1");
+        self.emit("lw\t$t1 12($t1)");
+        self.emit("lw\t$t2 12($a0)");
+        self.emit(&format!("{}\t$t1 $t1 $t2",instruction));
+        self.emit("sw\t$t1 12($a0)");
+    }
+}
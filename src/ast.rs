@@ -1,18 +1,18 @@
-use crate::token::Token;
+use crate::token::{Token,Span};
 
 
 /*
-operator precedence from highest to lowest
+operator precedence from highest to lowest , the parser takes it from the binding power table in parser.rs
 
 .
 @
-~ bitwie not
-isvoid 
-* /
-+ -
-<= < = >=   // = is equality operator . => is more than operator.
+~ bitwise not
+isvoid
+* /                 left associative
++ -                 left associative
+<= < = > >=         non associative , a < b < c is a syntax error . = is equality , > and >= are extensions
 not
-<- =>
+<-                  right associative
 
 */
 
@@ -87,7 +87,7 @@ pub trait  Visitor<T>{
     fn visit_integerliteral(&mut self,integerliteral:&Token)->T;
     fn visit_boolliteral(&mut self,boolliteral:&Token)->T;
     fn visit_id(&mut self,id:&Token)->T;
-    fn visit_dispatch(&mut self,target:&Option<Type>,expr:&Expr,method_name:&Option<identifier>,arguments:&Vec<Expr>)->T;
+    fn visit_dispatch(&mut self,id:NodeId,target:&Option<Type>,expr:&Expr,method_name:&Option<identifier>,arguments:&Vec<Expr>)->T;
 }
 
 
@@ -99,18 +99,26 @@ pub struct  Program{
 pub struct Class{
     type_ : Token,
     inherits : Option<Token>,
-    features : Vec<Feature>
+    features : Vec<Feature>,
+    span : Span,
+    filename : String
 }
 
 type Type = Token;
 type identifier = Token;
+
+//names a dispatch within its program , later stages key what they learn about a dispatch by it.
+//the program numbers its dispatches in source order , so the same source always gets the same ids
+pub type NodeId = usize;
+
 #[derive(Debug)]
 pub enum Feature {
     Method{
         id : identifier,
         type_ : Type,
         parameters : Vec<Formal>,
-        body : Expr
+        body : Expr,
+        span : Span
     },
     Attribute{
         id : Token,
@@ -130,7 +138,8 @@ pub enum Expr{
         right : Box<Expr>
     },
     Not{
-        expr : Box<Expr>
+        expr : Box<Expr>,
+        span : Span
     },
     Comparison{
         left : Box<Expr>,
@@ -148,23 +157,29 @@ pub enum Expr{
         right : Box<Expr>
     },
     New{
-        type_ : Token
+        type_ : Token,
+        span : Span
     },
     Delete{
-        expr : Box<Expr>
+        expr : Box<Expr>,
+        span : Span
     }
     ,
     IsVoid{
-        expr : Box<Expr>
+        expr : Box<Expr>,
+        span : Span
     },
     BitWiseNot{ //~ operator
-        expr : Box<Expr>
+        expr : Box<Expr>,
+        span : Span
     },
     Dispatch{
+        id : NodeId,
         target : Option<Type>,
         expr : Box<Expr>,
         method_name:Option<identifier>,
-        arguments : Vec<Expr>
+        arguments : Vec<Expr>,
+        span : Span
     },
     StringLiteral{
         value : Token
@@ -180,7 +195,8 @@ pub enum Expr{
     },
     Case{
         expr : Box<Expr>,
-        branches : Vec<Expr>
+        branches : Vec<Expr>,
+        span : Span
     },
     Branch{
         id : identifier,
@@ -191,16 +207,19 @@ pub enum Expr{
     If{
         Condition : Box<Expr>,
         Then : Box<Expr>,
-        Else : Box<Expr> 
+        Else : Box<Expr>,
+        span : Span
     },
     While{
         Condition : Box<Expr>,
-        Loop : Box<Expr>
+        Loop : Box<Expr>,
+        span : Span
     },
     Let{
 
         declarations : Vec<Expr>,//Declaration
-        body : Box<Expr>
+        body : Box<Expr>,
+        span : Span
     },
     Declaration{
         id : identifier,
@@ -208,17 +227,47 @@ pub enum Expr{
         expr : Box<Option<Expr>>
     },
     Block{
-        exprs : Vec<Expr>
+        exprs : Vec<Expr>,
+        span : Span
     },
     Grouping{
-        expr : Box<Expr>
+        expr : Box<Expr>,
+        span : Span
     },
     Error
 }
 
 impl Program{
     pub fn new(classes:Vec<Class>)->Program{
-        Program{classes}
+        let mut program = Program{classes};
+        program.number_dispatches();
+        program
+    }
+    pub fn classes(&self)->&Vec<Class>{
+        &self.classes
+    }
+    //records the file every class of the program came from
+    pub fn set_filename(&mut self,filename:&str){
+        for class in self.classes.iter_mut(){
+            class.set_filename(filename);
+        }
+    }
+    //adds the classes of another file , programs spread over several files are checked as one
+    pub fn extend(&mut self,other:Program){
+        self.classes.extend(other.classes);
+        self.number_dispatches();
+    }
+    fn number_dispatches(&mut self){
+        let mut next = 0;
+        for class in self.classes.iter_mut(){
+            for feature in class.features.iter_mut(){
+                match feature{
+                    Feature::Method{body,..} => body.number_dispatches(&mut next),
+                    Feature::Attribute{expr:Some(init),..} => init.number_dispatches(&mut next),
+                    _ => ()
+                }
+            }
+        }
     }
     pub fn accept<T>(&self,visitor:&mut dyn Visitor<T>)->T{
         visitor.visit_program(&self.classes)
@@ -226,8 +275,28 @@ impl Program{
 }
 
 impl Class{
-    pub fn new(type_:Token, inherits:Option<Token>, features:Vec<Feature>)->Class{
-        Class{type_,inherits,features}
+    pub fn new(type_:Token, inherits:Option<Token>, features:Vec<Feature>,span:Span)->Class{
+        Class{type_,inherits,features,span,filename:String::new()}
+    }
+    pub fn type_(&self)->&Token{
+        &self.type_
+    }
+    pub fn inherits(&self)->&Option<Token>{
+        &self.inherits
+    }
+    pub fn features(&self)->&Vec<Feature>{
+        &self.features
+    }
+    //from the class keyword to the closing brace
+    pub fn span(&self)->Span{
+        self.span
+    }
+    //file the class was read from , empty unless the driver set it
+    pub fn filename(&self)->&str{
+        &self.filename
+    }
+    pub fn set_filename(&mut self,filename:&str){
+        self.filename = filename.to_string();
     }
     pub fn accept<T>(&self,visitor:&mut dyn Visitor<T>)->T{
         visitor.visit_class( &self.type_, &self.inherits, &self.features)
@@ -235,24 +304,76 @@ impl Class{
 }
 
 impl Feature{
-    pub fn new_method(id:identifier,type_:Type,parameters:Vec<Formal>,body:Expr)->Feature{
-        Feature::Method{id,type_,parameters,body}
+    pub fn new_method(id:identifier,type_:Type,parameters:Vec<Formal>,body:Expr,span:Span)->Feature{
+        Feature::Method{id,type_,parameters,body,span}
     }
     pub fn new_attribute(id:Token,type_:Type,expr:Option<Expr>)->Feature{
         Feature::Attribute{id,type_,expr}
     }
     pub fn accept<T>(&self,visitor:&mut dyn Visitor<T>)->T{
         match self{
-            Feature::Method{id,type_,parameters,body} => visitor.visit_method(id,type_,parameters,body),
+            Feature::Method{id,type_,parameters,body,..} => visitor.visit_method(id,type_,parameters,body),
             Feature::Attribute{id,type_,expr} => visitor.visit_attribute(id,type_,expr)
         }
     }
+    pub fn id(&self)->&Token{
+        match self{
+            Feature::Method{id,..}|Feature::Attribute{id,..} => id
+        }
+    }
+    //return type of a method , declared type of an attribute
+    pub fn type_(&self)->&Token{
+        match self{
+            Feature::Method{type_,..}|Feature::Attribute{type_,..} => type_
+        }
+    }
+    pub fn is_method(&self)->bool{
+        matches!(self,Feature::Method{..})
+    }
+    //empty for attributes
+    pub fn parameters(&self)->&[Formal]{
+        match self{
+            Feature::Method{parameters,..} => parameters,
+            Feature::Attribute{..} => &[]
+        }
+    }
+    pub fn body(&self)->Option<&Expr>{
+        match self{
+            Feature::Method{body,..} => Some(body),
+            Feature::Attribute{..} => None
+        }
+    }
+    pub fn init(&self)->Option<&Expr>{
+        match self{
+            Feature::Attribute{expr,..} => expr.as_ref(),
+            Feature::Method{..} => None
+        }
+    }
+    //methods end at the closing brace , attributes at their initializer or type
+    pub fn span(&self)->Span{
+        match self{
+            Feature::Method{span,..} => *span,
+            Feature::Attribute{id,type_,expr} => match expr{
+                Some(expr) => id.span.to(expr.span()),
+                None => id.span.to(type_.span)
+            }
+        }
+    }
 }
 
 impl Formal{
     pub fn new(id:identifier,type_:Type)->Formal{
         Formal{id,type_}
     }
+    pub fn id(&self)->&Token{
+        &self.id
+    }
+    pub fn type_(&self)->&Token{
+        &self.type_
+    }
+    pub fn span(&self)->Span{
+        self.id.span.to(self.type_.span)
+    }
     pub fn accept<T>(&self,visitor:&mut dyn Visitor<T>)->T{
         visitor.visit_formal(&self.id,&self.type_)
     }
@@ -262,48 +383,127 @@ impl Expr{
     pub fn accept<T>(&self,visit:&mut dyn Visitor<T>)->T{
         match self{
             Expr::Assign{left,right} => visit.visit_assign(left,right),
-            Expr::Not{expr} => visit.visit_not(expr),
+            Expr::Not{expr,..} => visit.visit_not(expr),
             Expr::Comparison{left,operator,right} => visit.visit_comparison(left,operator,right),
             Expr::Arithmetic{left,operator,right} => visit.visit_arithmetic(left,operator,right),
             Expr::Factor{left,operator,right} => visit.visit_factor(left,operator,right),
-            Expr::New{type_} => visit.visit_new(type_),
-            Expr::IsVoid{expr} => visit.visit_isvoid(expr),
-            Expr::BitWiseNot{expr} => visit.visit_bitwise_not(expr),
+            Expr::New{type_,..} => visit.visit_new(type_),
+            Expr::IsVoid{expr,..} => visit.visit_isvoid(expr),
+            Expr::BitWiseNot{expr,..} => visit.visit_bitwise_not(expr),
             Expr::StringLiteral{value} => visit.visit_stringliteral(value),
             Expr::IntegerLiteral{value} => visit.visit_integerliteral(value),
             Expr::BoolLiteral{value} => visit.visit_boolliteral(value),
             Expr::ID{id} => visit.visit_id(id),
-            Expr::Case{expr,branches} => visit.visit_case(expr ,branches),
+            Expr::Case{expr,branches,..} => visit.visit_case(expr ,branches),
             Expr::Branch{id,type_,expr} => visit.visit_branch(id,type_,expr),
-            Expr::If{Condition,Then,Else} => visit.visit_if(&Condition,Then,Else),
-            Expr::While{Condition,Loop} => visit.visit_while(Condition,Loop),
-            Expr::Let{declarations,body} => visit.visit_let(declarations,body),
+            Expr::If{Condition,Then,Else,..} => visit.visit_if(Condition,Then,Else),
+            Expr::While{Condition,Loop,..} => visit.visit_while(Condition,Loop),
+            Expr::Let{declarations,body,..} => visit.visit_let(declarations,body),
             Expr::Declaration{id,type_,expr} => visit.visit_declaration(id,type_,expr),
-            Expr::Block{exprs} => visit.visit_block(exprs),
-            Expr::Grouping{expr} => visit.visit_grouping(expr),
-            Expr::Delete { expr } => visit.visit_delete(expr),
-            Expr::Dispatch { target, expr, method_name, arguments } => visit.visit_dispatch(target, expr, method_name, arguments),
+            Expr::Block{exprs,..} => visit.visit_block(exprs),
+            Expr::Grouping{expr,..} => visit.visit_grouping(expr),
+            Expr::Delete { expr,.. } => visit.visit_delete(expr),
+            Expr::Dispatch { id, target, expr, method_name, arguments,.. } => visit.visit_dispatch(*id, target, expr, method_name, arguments),
             _ => visit.not_implemented()
         }
     }
+
+    fn number_dispatches(&mut self,next:&mut NodeId){
+        match self{
+            Expr::Dispatch{id,expr,arguments,..} =>{
+                *id = *next;
+                *next += 1;
+                expr.number_dispatches(next);
+                for argument in arguments.iter_mut(){
+                    argument.number_dispatches(next);
+                }
+            },
+            Expr::Assign{left,right}|Expr::Comparison{left,right,..}|Expr::Arithmetic{left,right,..}|Expr::Factor{left,right,..} =>{
+                left.number_dispatches(next);
+                right.number_dispatches(next);
+            },
+            Expr::Not{expr,..}|Expr::Delete{expr,..}|Expr::IsVoid{expr,..}|Expr::BitWiseNot{expr,..}|Expr::Grouping{expr,..}|
+            Expr::Branch{expr,..} => expr.number_dispatches(next),
+            Expr::Declaration{expr,..} =>{
+                if let Some(init) = expr.as_mut(){
+                    init.number_dispatches(next);
+                }
+            },
+            Expr::Case{expr,branches:exprs,..}|Expr::Let{body:expr,declarations:exprs,..} =>{
+                expr.number_dispatches(next);
+                for e in exprs.iter_mut(){
+                    e.number_dispatches(next);
+                }
+            },
+            Expr::If{Condition,Then,Else,..} =>{
+                Condition.number_dispatches(next);
+                Then.number_dispatches(next);
+                Else.number_dispatches(next);
+            },
+            Expr::While{Condition,Loop,..} =>{
+                Condition.number_dispatches(next);
+                Loop.number_dispatches(next);
+            },
+            Expr::Block{exprs,..} =>{
+                for e in exprs.iter_mut(){
+                    e.number_dispatches(next);
+                }
+            },
+            Expr::New{..}|Expr::StringLiteral{..}|Expr::IntegerLiteral{..}|Expr::BoolLiteral{..}|Expr::ID{..}|Expr::Error => ()
+        }
+    }
+
+    //line of the expression the way the reference parser numbers it , the line of its first token .
+    //parentheses leave no node in the reference ast , so a group has the line of the expression inside.
+    //0 for Expr::Error
+    pub fn line(&self)->usize{
+        match self{
+            Expr::Grouping{expr,..} => expr.line(),
+            Expr::Assign{left,..}|Expr::Comparison{left,..}|Expr::Arithmetic{left,..}|Expr::Factor{left,..} => left.line(),
+            Expr::Dispatch{expr,..} => expr.line(),
+            Expr::StringLiteral{value}|Expr::IntegerLiteral{value}|Expr::BoolLiteral{value} => value.line,
+            Expr::ID{id} => id.line,
+            Expr::Let{declarations,..} => declarations.first().map_or(self.span().line,|d| d.span().line),
+            _ => self.span().line
+        }
+    }
+
+    //the whole subexpression , keywords and delimiters included
+    pub fn span(&self)->Span{
+        match self{
+            Expr::Not{span,..}|Expr::New{span,..}|Expr::Delete{span,..}|Expr::IsVoid{span,..}|Expr::BitWiseNot{span,..}|
+            Expr::Dispatch{span,..}|Expr::Case{span,..}|Expr::If{span,..}|Expr::While{span,..}|Expr::Let{span,..}|
+            Expr::Block{span,..}|Expr::Grouping{span,..} => *span,
+            Expr::Assign{left,right}|Expr::Comparison{left,right,..}|Expr::Arithmetic{left,right,..}|Expr::Factor{left,right,..} =>
+                left.span().to(right.span()),
+            Expr::StringLiteral{value}|Expr::IntegerLiteral{value}|Expr::BoolLiteral{value} => value.span,
+            Expr::ID{id} => id.span,
+            Expr::Branch{id,expr,..} => id.span.to(expr.span()),
+            Expr::Declaration{id,type_,expr} => match expr.as_ref(){
+                Some(expr) => id.span.to(expr.span()),
+                None => id.span.to(type_.span)
+            },
+            Expr::Error => Span::default()
+        }
+    }
 }
 
 
 impl Expr{
-    pub fn IF_EXPR(Condition:Expr,Then:Expr,Else:Expr)->Expr{
-        Expr::If{Condition:Box::new(Condition),Then:Box::new(Then),Else:Box::new(Else)}
+    pub fn IF_EXPR(Condition:Expr,Then:Expr,Else:Expr,span:Span)->Expr{
+        Expr::If{Condition:Box::new(Condition),Then:Box::new(Then),Else:Box::new(Else),span}
     }
-    pub fn BLOCK_EXPR(exprs:Vec<Expr>)->Expr{
-        Expr::Block{exprs}
+    pub fn BLOCK_EXPR(exprs:Vec<Expr>,span:Span)->Expr{
+        Expr::Block{exprs,span}
     }
-    pub fn WHILE_EXPR(condition:Expr,body:Expr)->Expr{
-        Expr::While{Condition:Box::new(condition),Loop:Box::new(body)}
+    pub fn WHILE_EXPR(condition:Expr,body:Expr,span:Span)->Expr{
+        Expr::While{Condition:Box::new(condition),Loop:Box::new(body),span}
     }
-    pub fn LET_EXPR(declarations:Vec<Expr>,body:Expr)->Expr{
-        Expr::Let{declarations,body:Box::new(body)}
+    pub fn LET_EXPR(declarations:Vec<Expr>,body:Expr,span:Span)->Expr{
+        Expr::Let{declarations,body:Box::new(body),span}
     }
-    pub fn CASE_EXPR(expr:Expr,branches:Vec<Expr>)->Expr{
-        Expr::Case{expr:Box::new(expr),branches}
+    pub fn CASE_EXPR(expr:Expr,branches:Vec<Expr>,span:Span)->Expr{
+        Expr::Case{expr:Box::new(expr),branches,span}
     }
     pub fn Declaration(id:identifier,type_:Type,expr:Option<Expr>)->Expr{
         Expr::Declaration{id,type_,expr:Box::new(expr)}
@@ -311,8 +511,8 @@ impl Expr{
     pub fn ID(id:identifier)->Expr{
         Expr::ID{id}
     }
-    pub fn Not(expr:Expr)->Expr{
-        Expr::Not{expr:Box::new(expr)}
+    pub fn Not(expr:Expr,span:Span)->Expr{
+        Expr::Not{expr:Box::new(expr),span}
     }
     pub fn Assign(left:Expr,right:Expr)->Expr{
         Expr::Assign{left:Box::new(left),right:Box::new(right)}
@@ -326,20 +526,20 @@ impl Expr{
     pub fn Factor(left:Expr,operator:Token,right:Expr)->Expr{
         Expr::Factor{left:Box::new(left),operator,right:Box::new(right)}
     }
-    pub fn New(type_:Token)->Expr{
-        Expr::New{type_}
+    pub fn New(type_:Token,span:Span)->Expr{
+        Expr::New{type_,span}
     }
-    pub fn Delete(expr:Expr)->Expr{
-        Expr::Delete{expr:Box::new(expr)}
+    pub fn Delete(expr:Expr,span:Span)->Expr{
+        Expr::Delete{expr:Box::new(expr),span}
     }
-    pub fn IsVoid(expr:Expr)->Expr{
-        Expr::IsVoid{expr:Box::new(expr)}
+    pub fn IsVoid(expr:Expr,span:Span)->Expr{
+        Expr::IsVoid{expr:Box::new(expr),span}
     }
-    pub fn BitWiseNot(expr:Expr)->Expr{
-        Expr::BitWiseNot{expr:Box::new(expr)}
+    pub fn BitWiseNot(expr:Expr,span:Span)->Expr{
+        Expr::BitWiseNot{expr:Box::new(expr),span}
     }
-    pub fn Grouping(expr:Expr)->Expr{
-        Expr::Grouping{expr:Box::new(expr)}
+    pub fn Grouping(expr:Expr,span:Span)->Expr{
+        Expr::Grouping{expr:Box::new(expr),span}
     }
    
     pub fn StringLiteral(value:Token)->Expr{
@@ -352,8 +552,9 @@ impl Expr{
         Expr::BoolLiteral{value}
     }
 
-    pub fn Dispatch(target:Option<Type>,expr:Expr,method_name:Option<identifier>,arguments:Vec<Expr>)->Expr{
-        Expr::Dispatch{target,expr:Box::new(expr),method_name,arguments}
+    pub fn Dispatch(target:Option<Type>,expr:Expr,method_name:Option<identifier>,arguments:Vec<Expr>,span:Span)->Expr{
+        //numbered by the program it ends up in
+        Expr::Dispatch{id:0,target,expr:Box::new(expr),method_name,arguments,span}
     }
     
 }
@@ -0,0 +1,337 @@
+use crate::ast::*;
+use crate::token::*;
+use crate::tokenprinter::escape;
+
+/*
+prints the ast the way the reference parser does (coolc -p) , so the output can be diffed against it
+
+#1
+_program
+  #1
+  _class
+    Main
+    IO
+    "examples/hello_world.cl"
+    (
+    #2
+    _method
+      main
+      SELF_TYPE
+      #3
+      _dispatch
+        #3
+        _object
+          self
+        : _no_type
+        out_string
+        (
+        #3
+        _string
+          "Hello, World.\n"
+        : _no_type
+        )
+      : _no_type
+    )
+
+every node starts with the line of its first token , every expression ends with its type , _no_type
+before semantic analysis . parentheses leave no node , a let with several bindings is a let per binding
+nested in the body of the one before , and f(x) is a dispatch to self . a class without a parent
+inherits Object.
+
+>, >= and delete are extensions of this compiler , they are printed as _gt , _geq and _delete.
+*/
+
+pub struct AstDump{
+    indent : usize,
+    filename : String
+}
+
+impl AstDump{
+    pub fn dump_program(program:&Program)->String{
+        let mut dump = AstDump{indent:0,filename:String::new()};
+        let line = program.classes().first().map_or(0,|c| c.span().line);
+        format!("#{}\n{}",line,program.accept(&mut dump))
+    }
+
+    fn pad(&self)->String{
+        " ".repeat(self.indent)
+    }
+
+    //a symbol or a value on its own line , one level below the node being printed
+    fn field(&self,value:&str)->String{
+        format!("{}  {}\n",self.pad(),value)
+    }
+
+    //a child expression one level down , with its line
+    fn child(&mut self,expr:&Expr)->String{
+        self.indent += 2;
+        let result = format!("{}#{}\n{}",self.pad(),expr.line(),expr.accept(self));
+        self.indent -= 2;
+        result
+    }
+
+    //the name of a node , its fields and the type of the expression
+    fn node(&self,name:&str,fields:String)->String{
+        format!("{}{}\n{}{}: _no_type\n",self.pad(),name,fields,self.pad())
+    }
+
+    fn binary(&mut self,left:&Expr,operator:&Token,right:&Expr)->String{
+        let name = match operator.tokentype{
+            TokenType::PLUS => "_plus",
+            TokenType::MINUS => "_sub",
+            TokenType::STAR => "_mul",
+            TokenType::SLASH => "_divide",
+            TokenType::LESSTHAN => "_lt",
+            TokenType::LESSEQUAL => "_leq",
+            TokenType::MORETHAN => "_gt",
+            TokenType::MOREEQUAL => "_geq",
+            _ => "_eq"
+        };
+        let fields = self.child(left) + &self.child(right);
+        self.node(name,fields)
+    }
+
+    fn unary(&mut self,name:&str,expr:&Expr)->String{
+        let fields = self.child(expr);
+        self.node(name,fields)
+    }
+
+    //_let for the first binding , the let for the rest is its body
+    fn let_chain(&mut self,declarations:&[Expr],body:&Expr)->String{
+        let Some((Expr::Declaration{id,type_,expr},rest)) = declarations.split_first() else{
+            return body.accept(self);
+        };
+        let mut fields = self.field(&id.lexeme) + &self.field(&type_.lexeme);
+        fields.push_str(&self.init(type_,expr.as_ref().as_ref()));
+        if rest.is_empty(){
+            fields.push_str(&self.child(body));
+        }
+        else{
+            self.indent += 2;
+            fields.push_str(&format!("{}#{}\n{}",self.pad(),rest[0].span().line,self.let_chain(rest,body)));
+            self.indent -= 2;
+        }
+        self.node("_let",fields)
+    }
+
+    //the initializer of an attribute or a let binding , _no_expr on the line of the type when there is none
+    fn init(&mut self,type_:&Token,expr:Option<&Expr>)->String{
+        match expr{
+            Some(expr) => self.child(expr),
+            None =>{
+                self.indent += 2;
+                let result = format!("{}#{}\n{}",self.pad(),type_.line,self.node("_no_expr",String::new()));
+                self.indent -= 2;
+                result
+            }
+        }
+    }
+}
+
+impl Visitor<String> for AstDump{
+    fn visit_program(&mut self,classes:&Vec<Class>)->String{
+        let mut result = format!("{}_program\n",self.pad());
+        self.indent += 2;
+        for class in classes{
+            self.filename = class.filename().to_string();
+            result.push_str(&format!("{}#{}\n",self.pad(),class.span().line));
+            result.push_str(&class.accept(self));
+        }
+        self.indent -= 2;
+        result
+    }
+
+    fn visit_class(&mut self,type_:&Token,inherits:&Option<Token>,features:&Vec<Feature>)->String{
+        let mut result = format!("{}_class\n",self.pad());
+        result.push_str(&self.field(&type_.lexeme));
+        result.push_str(&self.field(inherits.as_ref().map_or("Object",|t| &t.lexeme)));
+        result.push_str(&self.field(&format!("\"{}\"",escape(&self.filename))));
+        result.push_str(&self.field("("));
+        self.indent += 2;
+        for feature in features{
+            result.push_str(&format!("{}#{}\n",self.pad(),feature.id().line));
+            result.push_str(&feature.accept(self));
+        }
+        self.indent -= 2;
+        result.push_str(&self.field(")"));
+        result
+    }
+
+    fn visit_method(&mut self,id:&Token,type_:&Token,parameters:&Vec<Formal>,body:&Expr)->String{
+        let mut result = format!("{}_method\n",self.pad());
+        result.push_str(&self.field(&id.lexeme));
+        self.indent += 2;
+        for parameter in parameters{
+            result.push_str(&format!("{}#{}\n",self.pad(),parameter.id().line));
+            result.push_str(&parameter.accept(self));
+        }
+        self.indent -= 2;
+        result.push_str(&self.field(&type_.lexeme));
+        result.push_str(&self.child(body));
+        result
+    }
+
+    fn visit_attribute(&mut self,id:&Token,type_:&Token,expr:&Option<Expr>)->String{
+        let mut result = format!("{}_attr\n",self.pad());
+        result.push_str(&self.field(&id.lexeme));
+        result.push_str(&self.field(&type_.lexeme));
+        result.push_str(&self.init(type_,expr.as_ref()));
+        result
+    }
+
+    fn visit_formal(&mut self,id:&Token,type_:&Token)->String{
+        format!("{}_formal\n{}{}",self.pad(),self.field(&id.lexeme),self.field(&type_.lexeme))
+    }
+
+    fn visit_expr(&mut self,expr:&Expr)->String{
+        expr.accept(self)
+    }
+
+    fn visit_branch(&mut self,id:&Token,type_:&Token,expr:&Expr)->String{
+        let mut result = format!("{}_branch\n",self.pad());
+        result.push_str(&self.field(&id.lexeme));
+        result.push_str(&self.field(&type_.lexeme));
+        result.push_str(&self.child(expr));
+        result
+    }
+
+    //only reached through a let , which prints its bindings itself
+    fn visit_declaration(&mut self,id:&Token,type_:&Token,expr:&Option<Expr>)->String{
+        let fields = self.field(&id.lexeme) + &self.field(&type_.lexeme) + &self.init(type_,expr.as_ref());
+        self.node("_let",fields)
+    }
+
+    fn visit_block(&mut self,exprs:&Vec<Expr>)->String{
+        let mut fields = String::new();
+        for expr in exprs{
+            fields.push_str(&self.child(expr));
+        }
+        self.node("_block",fields)
+    }
+
+    fn visit_let(&mut self,declarations:&Vec<Expr>,body:&Expr)->String{
+        self.let_chain(declarations,body)
+    }
+
+    fn visit_while(&mut self,condition:&Expr,body:&Expr)->String{
+        let fields = self.child(condition) + &self.child(body);
+        self.node("_loop",fields)
+    }
+
+    fn visit_if(&mut self,condition:&Expr,body:&Expr,else_expr:&Expr)->String{
+        let fields = self.child(condition) + &self.child(body) + &self.child(else_expr);
+        self.node("_cond",fields)
+    }
+
+    fn visit_case(&mut self,condition:&Expr,branches:&Vec<Expr>)->String{
+        let mut fields = self.child(condition);
+        self.indent += 2;
+        for branch in branches{
+            fields.push_str(&format!("{}#{}\n{}",self.pad(),branch.span().line,branch.accept(self)));
+        }
+        self.indent -= 2;
+        self.node("_typcase",fields)
+    }
+
+    fn visit_arithmetic(&mut self,left:&Expr,operator:&Token,right:&Expr)->String{
+        self.binary(left,operator,right)
+    }
+
+    fn visit_comparison(&mut self,left:&Expr,operator:&Token,right:&Expr)->String{
+        self.binary(left,operator,right)
+    }
+
+    fn visit_factor(&mut self,left:&Expr,operator:&Token,right:&Expr)->String{
+        self.binary(left,operator,right)
+    }
+
+    fn visit_assign(&mut self,left:&Expr,right:&Expr)->String{
+        let name = match left{
+            Expr::ID{id} => id.lexeme.clone(),
+            _ => String::new()
+        };
+        let fields = self.field(&name) + &self.child(right);
+        self.node("_assign",fields)
+    }
+
+    fn visit_not(&mut self,not_expr:&Expr)->String{
+        self.unary("_comp",not_expr)
+    }
+
+    fn visit_new(&mut self,new_expr:&Token)->String{
+        let fields = self.field(&new_expr.lexeme);
+        self.node("_new",fields)
+    }
+
+    fn visit_delete(&mut self,delete_expr:&Expr)->String{
+        self.unary("_delete",delete_expr)
+    }
+
+    fn visit_isvoid(&mut self,isvoid_expr:&Expr)->String{
+        self.unary("_isvoid",isvoid_expr)
+    }
+
+    fn visit_bitwise_not(&mut self,bitwise_not_expr:&Expr)->String{
+        self.unary("_neg",bitwise_not_expr)
+    }
+
+    fn visit_grouping(&mut self,grouping_expr:&Expr)->String{
+        grouping_expr.accept(self)
+    }
+
+    fn not_implemented(&mut self)->String{
+        self.node("_no_expr",String::new())
+    }
+
+    fn visit_stringliteral(&mut self,stringliteral:&Token)->String{
+        let fields = self.field(&format!("\"{}\"",escape(stringliteral.string_value())));
+        self.node("_string",fields)
+    }
+
+    fn visit_integerliteral(&mut self,integerliteral:&Token)->String{
+        let fields = self.field(&integerliteral.lexeme);
+        self.node("_int",fields)
+    }
+
+    fn visit_boolliteral(&mut self,boolliteral:&Token)->String{
+        let fields = self.field(if boolliteral.bool_value() {"1"} else {"0"});
+        self.node("_bool",fields)
+    }
+
+    fn visit_id(&mut self,id:&Token)->String{
+        let fields = self.field(&id.lexeme);
+        self.node("_object",fields)
+    }
+
+    fn visit_dispatch(&mut self,_id:NodeId,target:&Option<Token>,expr:&Expr,method_name:&Option<Token>,arguments:&Vec<Expr>)->String{
+        let mut fields = String::new();
+        let name = match method_name{
+            Some(name) =>{
+                fields.push_str(&self.child(expr));
+                name.lexeme.clone()
+            },
+            //f(x) , a dispatch to self
+            None =>{
+                let line = expr.line();
+                self.indent += 2;
+                let self_ = format!("{}_object\n{}{}: _no_type\n",self.pad(),self.field("self"),self.pad());
+                fields.push_str(&format!("{}#{}\n{}",self.pad(),line,self_));
+                self.indent -= 2;
+                match expr{
+                    Expr::ID{id} => id.lexeme.clone(),
+                    _ => String::new()
+                }
+            }
+        };
+        if let Some(target) = target{
+            fields.push_str(&self.field(&target.lexeme));
+        }
+        fields.push_str(&self.field(&name));
+        fields.push_str(&self.field("("));
+        for argument in arguments{
+            fields.push_str(&self.child(argument));
+        }
+        fields.push_str(&self.field(")"));
+        self.node(if target.is_some() {"_static_dispatch"} else {"_dispatch"},fields)
+    }
+}
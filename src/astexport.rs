@@ -0,0 +1,300 @@
+use crate::ast::*;
+use crate::token::*;
+
+/*
+the ast as json (--emit=ast-json) or as an s-expression (--emit=ast-sexp) , for tools outside the compiler
+
+schema , version 1
+
+every node has a kind and the fields below in that order . the program is the root , it has no span.
+a span is the byte offsets start (inclusive) and end (exclusive) in the file of its class , and the line and
+column (both from 1) where it starts . nodes read from an ast dump only know their line , the rest is 0.
+a token is a name , type or operator as written in the source , its lexeme and its span.
+
+  program     version (1) , classes [class]
+  class       span , filename , name token , parent token or null , features [method or attribute]
+  method      span , name token , formals [formal] , return_type token , body expr
+  attribute   span , name token , type token , init expr or null
+  formal      span , name token , type token
+
+expressions
+  assign      span , name token , value expr
+  dispatch    span , receiver expr or null (f(x) has no receiver , it calls self) ,
+              static_type token or null (the T of e@T.f()) , method token , arguments [expr]
+  if          span , condition expr , then expr , else expr
+  while       span , condition expr , body expr
+  block       span , body [expr]
+  let         span , bindings [binding] , body expr
+  binding     span , name token , type token , init expr or null
+  case        span , expr expr , branches [branch]
+  branch      span , name token , type token , body expr
+  binary      span , operator token (+ - * / < <= = > >=) , left expr , right expr
+  not         span , operand expr
+  complement  span , operand expr , the ~ of integers
+  isvoid      span , operand expr
+  delete      span , operand expr
+  group       span , expr expr , an expression in parentheses
+  new         span , type token
+  object      span , name token , a name used as a value , self included
+  int         span , lexeme , value (a number)
+  string      span , lexeme (quotes and escapes as written) , value (the decoded text)
+  bool        span , lexeme , value (true or false)
+
+json writes a node as {"kind":"class","span":...,"name":...} , a span as
+{"start":0,"end":5,"line":1,"column":1} and a token as {"lexeme":"Main","span":{...}}
+
+the s-expression writes a node as (class (span 0 5 1 1) (name (token "Main" (span 6 10 1 7))) ...) ,
+the kind first and the span of the node without a field around it , a list as (...) , null as nil and the
+booleans as true and false . strings are quoted with the escapes of json in both.
+
+within a version fields and kinds are only ever added , never renamed , removed or reordered , so readers
+should ignore what they do not know . anything else is a new version.
+*/
+
+pub const VERSION:i64 = 1;
+
+//the tree both formats are written from
+enum Value{
+    NULL,
+    BOOL(bool),
+    INT(i64),
+    STR(String),
+    LIST(Vec<Value>),
+    SPAN(Span),
+    TOKEN(String,Span),
+    NODE(&'static str,Vec<(&'static str,Value)>)
+}
+
+pub fn json(program:&Program)->String{
+    let mut out = String::new();
+    write_json(&mut out,&self::program(program),0);
+    out.push('\n');
+    out
+}
+
+pub fn sexp(program:&Program)->String{
+    let mut out = String::new();
+    write_sexp(&mut out,&self::program(program),0);
+    out.push('\n');
+    out
+}
+
+fn program(program:&Program)->Value{
+    Value::NODE("program",vec![
+        ("version",Value::INT(VERSION)),
+        ("classes",Value::LIST(program.classes().iter().map(class).collect()))
+    ])
+}
+
+fn token(token:&Token)->Value{
+    Value::TOKEN(token.lexeme.clone(),token.span)
+}
+
+fn optional(value:Option<Value>)->Value{
+    value.unwrap_or(Value::NULL)
+}
+
+fn list(exprs:&[Expr])->Value{
+    Value::LIST(exprs.iter().map(expr).collect())
+}
+
+fn class(class:&Class)->Value{
+    Value::NODE("class",vec![
+        ("span",Value::SPAN(class.span())),
+        ("filename",Value::STR(class.filename().to_string())),
+        ("name",token(class.type_())),
+        ("parent",optional(class.inherits().as_ref().map(token))),
+        ("features",Value::LIST(class.features().iter().map(feature).collect()))
+    ])
+}
+
+fn feature(feature:&Feature)->Value{
+    let span = ("span",Value::SPAN(feature.span()));
+    match feature{
+        Feature::Method{id,type_,parameters,body,..} => Value::NODE("method",vec![
+            span,
+            ("name",token(id)),
+            ("formals",Value::LIST(parameters.iter().map(formal).collect())),
+            ("return_type",token(type_)),
+            ("body",expr(body))
+        ]),
+        Feature::Attribute{id,type_,expr:init} => Value::NODE("attribute",vec![
+            span,
+            ("name",token(id)),
+            ("type",token(type_)),
+            ("init",optional(init.as_ref().map(expr)))
+        ])
+    }
+}
+
+fn formal(formal:&Formal)->Value{
+    Value::NODE("formal",vec![
+        ("span",Value::SPAN(formal.span())),
+        ("name",token(formal.id())),
+        ("type",token(formal.type_()))
+    ])
+}
+
+fn expr(e:&Expr)->Value{
+    let span = ("span",Value::SPAN(e.span()));
+    let (kind,fields) = match e{
+        Expr::Assign{left,right} =>{
+            let name = match left.as_ref(){
+                Expr::ID{id} => token(id),
+                other => expr(other)
+            };
+            ("assign",vec![("name",name),("value",expr(right))])
+        },
+        Expr::Dispatch{target,expr:receiver,method_name,arguments,..} =>{
+            //f(x) keeps the name of the method in expr and has no method_name
+            let (receiver,method) = match method_name{
+                Some(method) => (expr(receiver),token(method)),
+                None => (Value::NULL,match receiver.as_ref(){
+                    Expr::ID{id} => token(id),
+                    other => expr(other)
+                })
+            };
+            ("dispatch",vec![
+                ("receiver",receiver),
+                ("static_type",optional(target.as_ref().map(token))),
+                ("method",method),
+                ("arguments",list(arguments))
+            ])
+        },
+        Expr::If{Condition,Then,Else,..} => ("if",vec![("condition",expr(Condition)),("then",expr(Then)),("else",expr(Else))]),
+        Expr::While{Condition,Loop,..} => ("while",vec![("condition",expr(Condition)),("body",expr(Loop))]),
+        Expr::Block{exprs,..} => ("block",vec![("body",list(exprs))]),
+        Expr::Let{declarations,body,..} => ("let",vec![("bindings",list(declarations)),("body",expr(body))]),
+        Expr::Declaration{id,type_,expr:init} =>
+            ("binding",vec![("name",token(id)),("type",token(type_)),("init",optional(init.as_ref().as_ref().map(expr)))]),
+        Expr::Case{expr:scrutinee,branches,..} => ("case",vec![("expr",expr(scrutinee)),("branches",list(branches))]),
+        Expr::Branch{id,type_,expr:body} => ("branch",vec![("name",token(id)),("type",token(type_)),("body",expr(body))]),
+        Expr::Comparison{left,operator,right}|Expr::Arithmetic{left,operator,right}|Expr::Factor{left,operator,right} =>
+            ("binary",vec![("operator",token(operator)),("left",expr(left)),("right",expr(right))]),
+        Expr::Not{expr:operand,..} => ("not",vec![("operand",expr(operand))]),
+        Expr::BitWiseNot{expr:operand,..} => ("complement",vec![("operand",expr(operand))]),
+        Expr::IsVoid{expr:operand,..} => ("isvoid",vec![("operand",expr(operand))]),
+        Expr::Delete{expr:operand,..} => ("delete",vec![("operand",expr(operand))]),
+        Expr::Grouping{expr:inner,..} => ("group",vec![("expr",expr(inner))]),
+        Expr::New{type_,..} => ("new",vec![("type",token(type_))]),
+        Expr::ID{id} => ("object",vec![("name",token(id))]),
+        Expr::IntegerLiteral{value} =>
+            ("int",vec![("lexeme",Value::STR(value.lexeme.clone())),("value",Value::INT(value.int_value() as i64))]),
+        Expr::StringLiteral{value} =>
+            ("string",vec![("lexeme",Value::STR(value.lexeme.clone())),("value",Value::STR(value.string_value().to_string()))]),
+        Expr::BoolLiteral{value} =>
+            ("bool",vec![("lexeme",Value::STR(value.lexeme.clone())),("value",Value::BOOL(value.bool_value()))]),
+        //only left by error recovery , which never reaches a dump
+        Expr::Error => ("error",vec![])
+    };
+    let mut all = vec![span];
+    all.extend(fields);
+    Value::NODE(kind,all)
+}
+
+//json string escapes , control characters as \u00XX
+fn quote(value:&str)->String{
+    let mut quoted = String::from("\"");
+    for c in value.chars(){
+        match c{
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\u{8}' => quoted.push_str("\\b"),
+            '\u{c}' => quoted.push_str("\\f"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => quoted.push_str(&format!("\\u{:04x}",c as u32)),
+            c => quoted.push(c)
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn pad(out:&mut String,indent:usize){
+    out.push('\n');
+    out.push_str(&"  ".repeat(indent));
+}
+
+//nodes and non empty lists take a line per element , everything else stays on one line
+fn write_json(out:&mut String,value:&Value,indent:usize){
+    match value{
+        Value::NULL => out.push_str("null"),
+        Value::BOOL(b) => out.push_str(&b.to_string()),
+        Value::INT(n) => out.push_str(&n.to_string()),
+        Value::STR(s) => out.push_str(&quote(s)),
+        Value::SPAN(s) => out.push_str(&format!("{{\"start\":{},\"end\":{},\"line\":{},\"column\":{}}}",s.start,s.end,s.line,s.column)),
+        Value::TOKEN(lexeme,span) =>{
+            out.push_str(&format!("{{\"lexeme\":{},\"span\":",quote(lexeme)));
+            write_json(out,&Value::SPAN(*span),indent);
+            out.push('}');
+        },
+        Value::LIST(values) if values.is_empty() => out.push_str("[]"),
+        Value::LIST(values) =>{
+            out.push('[');
+            for (i,value) in values.iter().enumerate(){
+                if i > 0{
+                    out.push(',');
+                }
+                pad(out,indent + 1);
+                write_json(out,value,indent + 1);
+            }
+            pad(out,indent);
+            out.push(']');
+        },
+        Value::NODE(kind,fields) =>{
+            out.push('{');
+            pad(out,indent + 1);
+            out.push_str(&format!("\"kind\":{}",quote(kind)));
+            for (name,value) in fields{
+                out.push(',');
+                pad(out,indent + 1);
+                out.push_str(&format!("{}:",quote(name)));
+                write_json(out,value,indent + 1);
+            }
+            pad(out,indent);
+            out.push('}');
+        }
+    }
+}
+
+fn write_sexp(out:&mut String,value:&Value,indent:usize){
+    match value{
+        Value::NULL => out.push_str("nil"),
+        Value::BOOL(b) => out.push_str(&b.to_string()),
+        Value::INT(n) => out.push_str(&n.to_string()),
+        Value::STR(s) => out.push_str(&quote(s)),
+        Value::SPAN(s) => out.push_str(&format!("(span {} {} {} {})",s.start,s.end,s.line,s.column)),
+        Value::TOKEN(lexeme,span) =>{
+            out.push_str(&format!("(token {} ",quote(lexeme)));
+            write_sexp(out,&Value::SPAN(*span),indent);
+            out.push(')');
+        },
+        Value::LIST(values) if values.is_empty() => out.push_str("()"),
+        Value::LIST(values) =>{
+            out.push('(');
+            for value in values{
+                pad(out,indent + 1);
+                write_sexp(out,value,indent + 1);
+            }
+            out.push(')');
+        },
+        Value::NODE(kind,fields) =>{
+            out.push('(');
+            out.push_str(kind);
+            for (name,value) in fields{
+                pad(out,indent + 1);
+                //(span 0 5 1 1) rather than (span (span 0 5 1 1))
+                if let Value::SPAN(_) = value{
+                    write_sexp(out,value,indent + 1);
+                    continue;
+                }
+                out.push_str(&format!("({} ",name));
+                write_sexp(out,value,indent + 1);
+                out.push(')');
+            }
+            out.push(')');
+        }
+    }
+}
@@ -16,7 +16,7 @@ impl Visitor<String> for AstPrinter{
         let mut result = String::new();
         for class in classes{
             result.push_str(&class.accept(self));
-            result.push_str("\n");
+            result.push('\n');
         }
         result
     }
@@ -42,7 +42,7 @@ impl Visitor<String> for AstPrinter{
         if let Some(expr) = expr{
             result.push_str(" <- (");
             result.push_str(&expr.accept(self));
-            result.push_str(")");
+            result.push(')');
         }
 
         result
@@ -53,7 +53,7 @@ impl Visitor<String> for AstPrinter{
         result.push_str(&format!("{} (",id.lexeme));
         for parameter in parameters{
             result.push_str(&parameter.accept(self));
-            result.push_str(",");
+            result.push(',');
         }
         if result.ends_with(","){
             result.pop();
@@ -129,7 +129,7 @@ impl Visitor<String> for AstPrinter{
         result.push_str(" of\n");
         for branch in branches{
             result.push_str(&branch.accept(self));
-            result.push_str("\n");
+            result.push('\n');
         }
         result.push_str("esac\n");
         result
@@ -156,11 +156,11 @@ impl Visitor<String> for AstPrinter{
         result.push_str("let\n");
         for declaration in declarations{
             result.push_str(&declaration.accept(self));
-            result.push_str("\n");
+            result.push('\n');
         }
         result.push_str("in\n");
         result.push_str(&body.accept(self));
-        result.push_str("\n");
+        result.push('\n');
         result
     }
 
@@ -187,28 +187,22 @@ impl Visitor<String> for AstPrinter{
     }
 
     fn visit_stringliteral(&mut self,stringliteral:&Token)->String {
-        format!("\"{}\"",stringliteral.lexeme.clone())
+        stringliteral.lexeme.clone()
     }
-    fn visit_dispatch(&mut self,target:&Option<Token>,expr:&Expr,method_name:&Option<Token>,arguments:&Vec<Expr>)->String {
+    fn visit_dispatch(&mut self,_id:NodeId,target:&Option<Token>,expr:&Expr,method_name:&Option<Token>,arguments:&Vec<Expr>)->String {
         let mut result = String::new();
         result.push_str(&expr.accept(self));
-        match target {
-            Some(tok) => {result.push_str("@");result.push_str(&tok.lexeme.clone())},
-            None => ()
-        };
-        match method_name {
-            Some(tok) => {result.push_str(".");result.push_str(&tok.lexeme.clone())},
-            None => ()
-        };
-        result.push_str("(");
+        if let Some(tok) = target {result.push('@');result.push_str(&tok.lexeme.clone())};
+        if let Some(tok) = method_name {result.push('.');result.push_str(&tok.lexeme.clone())};
+        result.push('(');
         for expr in arguments{
             result.push_str(&expr.accept(self));
-            result.push_str(",");
+            result.push(',');
         }
         if result.ends_with(","){
             result.pop();
         }
-        result.push_str(")");
+        result.push(')');
         result
     }
     fn not_implemented(&mut self)->String {
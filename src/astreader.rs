@@ -0,0 +1,384 @@
+use crate::ast::*;
+use crate::token::*;
+use crate::diagnostic::{Diagnostic,MALFORMED_AST};
+
+/*
+reads the ast printed by the reference parser (coolc -p) , or by astdump , back into an ast::Program ,
+so semant and asm can be run on the tree of the reference parser without going through ours
+
+every node is a #line , its name and its fields one level (two spaces) further in , expressions end
+with their type . the type is not kept , so the typed dump of coolc -s reads the same as the untyped one.
+
+the dump only has lines , every token of a node gets the line of the node and a span with just that line .
+it cannot tell some programs apart , they read back as one of them
+  f(x)                        self.f(x) , both are a dispatch to self
+  let x : Int , y : Int in e  let x : Int in let y : Int in e , a _let per binding
+  class A inherits Object     class A , a parent of Object is no inherits clause
+  (e)                         e , parentheses leave no node
+a dispatch whose method name is on a later line than its receiver reports the line of the receiver
+when it aborts at run time , like the code of the reference compiler does
+
+the first problem stops the reader , it is reported as E0200 with the span of the line in the dump
+*/
+
+//a non blank line of the dump , without its indentation
+#[derive(Clone,Copy)]
+struct Line<'a>{
+    indent : usize,
+    text : &'a str,
+    span : Span
+}
+
+pub struct AstReader<'a>{
+    lines : Vec<Line<'a>>,
+    current : usize,
+    end : Span
+}
+
+impl<'a> AstReader<'a>{
+    pub fn read_program(text:&'a str)->Result<Program,Diagnostic>{
+        let mut lines = vec![];
+        let mut start = 0;
+        for (i,line) in text.split_inclusive('\n').enumerate(){
+            let trimmed = line.trim_end();
+            let content = trimmed.trim_start_matches(' ');
+            let indent = trimmed.len() - content.len();
+            if !content.is_empty(){
+                let span = Span::new(start + indent,start + trimmed.len(),i + 1,indent + 1);
+                lines.push(Line{indent,text:content,span});
+            }
+            start += line.len();
+        }
+        //problems at the end of the dump point just after its last line
+        let end = lines.last().map_or(Span::new(0,0,1,1),|l| Span::new(l.span.end,l.span.end,l.span.line,l.indent + l.text.len() + 1));
+        let mut reader = AstReader{lines,current:0,end};
+        reader.program()
+    }
+
+    fn error(&self,line:&Line,message:String)->Diagnostic{
+        Diagnostic::error(MALFORMED_AST,message,line.span)
+    }
+
+    fn peek(&self)->Option<&Line<'a>>{
+        self.lines.get(self.current)
+    }
+
+    //a #line at the given indentation , the start of another node
+    fn at_node(&self,indent:usize)->bool{
+        self.peek().is_some_and(|l| l.indent == indent && l.text.starts_with('#'))
+    }
+
+    //the next line , which has to be at the given indentation
+    fn next(&mut self,indent:usize,what:&str)->Result<Line<'a>,Diagnostic>{
+        let Some(line) = self.peek().copied() else{
+            return Err(Diagnostic::error(MALFORMED_AST,format!("expected {} , found the end of the dump",what),self.end));
+        };
+        if line.indent != indent{
+            return Err(self.error(&line,format!("expected {} indented by {} , found '{}' indented by {}",what,indent,line.text,line.indent)));
+        }
+        self.current += 1;
+        Ok(line)
+    }
+
+    fn keyword(&mut self,indent:usize,keyword:&str)->Result<Line<'a>,Diagnostic>{
+        let line = self.next(indent,&format!("'{}'",keyword))?;
+        if line.text != keyword{
+            return Err(self.error(&line,format!("expected '{}' , found '{}'",keyword,line.text)));
+        }
+        Ok(line)
+    }
+
+    fn line_number(&mut self,indent:usize)->Result<usize,Diagnostic>{
+        let line = self.next(indent,"a line number like #1")?;
+        match line.text.strip_prefix('#').map(|n| n.parse::<usize>()){
+            Some(Ok(number)) => Ok(number),
+            _ => Err(self.error(&line,format!("expected a line number like #1 , found '{}'",line.text)))
+        }
+    }
+
+    //#line and the name of the node
+    fn node(&mut self,indent:usize)->Result<(usize,Line<'a>),Diagnostic>{
+        let number = self.line_number(indent)?;
+        let name = self.next(indent,"the name of a node")?;
+        Ok((number,name))
+    }
+
+    //a name of an object (lowercase) or of a type (uppercase) , as a token on the line of its node
+    fn name(&mut self,indent:usize,tokentype:TokenType,number:usize)->Result<Token,Diagnostic>{
+        let what = if tokentype == TokenType::TYPEID {"a type name"} else {"an object name"};
+        let line = self.next(indent,what)?;
+        let mut chars = line.text.chars();
+        let first = chars.next().is_some_and(|c| if tokentype == TokenType::TYPEID {c.is_ascii_uppercase()} else {c.is_ascii_lowercase()});
+        if !first || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_'){
+            return Err(self.error(&line,format!("expected {} , found '{}'",what,line.text)));
+        }
+        Ok(Token::new(number,span(number),line.text.to_string(),tokentype,None))
+    }
+
+    fn program(&mut self)->Result<Program,Diagnostic>{
+        self.line_number(0)?;
+        self.keyword(0,"_program")?;
+        let mut classes = vec![];
+        while self.peek().is_some(){
+            classes.push(self.class()?);
+        }
+        if classes.is_empty(){
+            return Err(Diagnostic::error(MALFORMED_AST,"a program needs at least one class".to_string(),self.end));
+        }
+        Ok(Program::new(classes))
+    }
+
+    fn class(&mut self)->Result<Class,Diagnostic>{
+        let number = self.line_number(2)?;
+        self.keyword(2,"_class")?;
+        let type_ = self.name(4,TokenType::TYPEID,number)?;
+        let parent = self.name(4,TokenType::TYPEID,number)?;
+        let inherits = if parent.lexeme == "Object" {None} else {Some(parent)};
+        let filename = self.next(4,"the file name of the class")?;
+        let filename = self.string(&filename)?;
+        self.keyword(4,"(")?;
+        let mut features = vec![];
+        while self.at_node(4){
+            features.push(self.feature()?);
+        }
+        self.keyword(4,")")?;
+        let mut class = Class::new(type_,inherits,features,span(number));
+        class.set_filename(&filename);
+        Ok(class)
+    }
+
+    fn feature(&mut self)->Result<Feature,Diagnostic>{
+        let (number,name) = self.node(4)?;
+        if name.text != "_method" && name.text != "_attr"{
+            return Err(self.error(&name,format!("expected '_method' or '_attr' , found '{}'",name.text)));
+        }
+        let id = self.name(6,TokenType::OBJECTID,number)?;
+        match name.text{
+            "_method" =>{
+                let mut formals = vec![];
+                while self.at_node(6){
+                    let number = self.line_number(6)?;
+                    self.keyword(6,"_formal")?;
+                    let id = self.name(8,TokenType::OBJECTID,number)?;
+                    formals.push(Formal::new(id,self.name(8,TokenType::TYPEID,number)?));
+                }
+                let type_ = self.name(6,TokenType::TYPEID,number)?;
+                let body = self.expr(6)?;
+                Ok(Feature::new_method(id,type_,formals,body,span(number)))
+            },
+            _ =>{
+                let type_ = self.name(6,TokenType::TYPEID,number)?;
+                Ok(Feature::new_attribute(id,type_,self.optional_expr(6)?))
+            }
+        }
+    }
+
+    fn expr(&mut self,indent:usize)->Result<Expr,Diagnostic>{
+        match self.expression(indent)?{
+            (_,Some(expr)) => Ok(expr),
+            (name,None) => Err(self.error(&name,"'_no_expr' where an expression is required".to_string()))
+        }
+    }
+
+    //_no_expr is only allowed for the initializer of an attribute or a let
+    fn optional_expr(&mut self,indent:usize)->Result<Option<Expr>,Diagnostic>{
+        Ok(self.expression(indent)?.1)
+    }
+
+    //one expression node , with the line of its name , none for _no_expr
+    fn expression(&mut self,indent:usize)->Result<(Line<'a>,Option<Expr>),Diagnostic>{
+        let (number,name) = self.node(indent)?;
+        let field = indent + 2;
+        let span = span(number);
+        let expr = match name.text{
+            "_no_expr" => None,
+            "_assign" =>{
+                let id = self.name(field,TokenType::OBJECTID,number)?;
+                Some(Expr::Assign(Expr::ID(id),self.expr(field)?))
+            },
+            "_static_dispatch" =>{
+                let expr = self.expr(field)?;
+                let type_ = self.name(field,TokenType::TYPEID,number)?;
+                let method = self.name(field,TokenType::OBJECTID,number)?;
+                Some(Expr::Dispatch(Some(type_),expr,Some(method),self.arguments(field)?,span))
+            },
+            "_dispatch" =>{
+                let expr = self.expr(field)?;
+                let method = self.name(field,TokenType::OBJECTID,number)?;
+                Some(Expr::Dispatch(None,expr,Some(method),self.arguments(field)?,span))
+            },
+            "_cond" =>{
+                let condition = self.expr(field)?;
+                let then = self.expr(field)?;
+                Some(Expr::IF_EXPR(condition,then,self.expr(field)?,span))
+            },
+            "_loop" =>{
+                let condition = self.expr(field)?;
+                Some(Expr::WHILE_EXPR(condition,self.expr(field)?,span))
+            },
+            "_block" =>{
+                let mut exprs = vec![];
+                while self.at_node(field){
+                    exprs.push(self.expr(field)?);
+                }
+                if exprs.is_empty(){
+                    return Err(self.error(&name,"a block needs at least one expression".to_string()));
+                }
+                Some(Expr::BLOCK_EXPR(exprs,span))
+            },
+            "_let" =>{
+                let id = self.name(field,TokenType::OBJECTID,number)?;
+                let type_ = self.name(field,TokenType::TYPEID,number)?;
+                let init = self.optional_expr(field)?;
+                let body = self.expr(field)?;
+                Some(Expr::LET_EXPR(vec![Expr::Declaration(id,type_,init)],body,span))
+            },
+            "_typcase" =>{
+                let expr = self.expr(field)?;
+                let mut branches = vec![];
+                while self.at_node(field){
+                    let number = self.line_number(field)?;
+                    self.keyword(field,"_branch")?;
+                    let id = self.name(field + 2,TokenType::OBJECTID,number)?;
+                    let type_ = self.name(field + 2,TokenType::TYPEID,number)?;
+                    branches.push(Expr::Branch{id,type_,expr:Box::new(self.expr(field + 2)?)});
+                }
+                if branches.is_empty(){
+                    return Err(self.error(&name,"a case needs at least one branch".to_string()));
+                }
+                Some(Expr::CASE_EXPR(expr,branches,span))
+            },
+            "_plus"|"_sub"|"_mul"|"_divide"|"_lt"|"_leq"|"_eq"|"_gt"|"_geq" =>{
+                let (tokentype,lexeme) = operator(name.text);
+                let operator = Token::new(number,span,lexeme.to_string(),tokentype,None);
+                let left = self.expr(field)?;
+                let right = self.expr(field)?;
+                Some(match tokentype{
+                    TokenType::PLUS|TokenType::MINUS => Expr::Arithmetic(left,operator,right),
+                    TokenType::STAR|TokenType::SLASH => Expr::Factor(left,operator,right),
+                    _ => Expr::Comparison(left,operator,right)
+                })
+            },
+            "_comp" => Some(Expr::Not(self.expr(field)?,span)),
+            "_neg" => Some(Expr::BitWiseNot(self.expr(field)?,span)),
+            "_isvoid" => Some(Expr::IsVoid(self.expr(field)?,span)),
+            "_delete" => Some(Expr::Delete(self.expr(field)?,span)),
+            "_new" => Some(Expr::New(self.name(field,TokenType::TYPEID,number)?,span)),
+            "_object" => Some(Expr::ID(self.name(field,TokenType::OBJECTID,number)?)),
+            "_int" =>{
+                let line = self.next(field,"an integer")?;
+                let value = match line.text.parse::<i32>(){
+                    Ok(value) if line.text.bytes().all(|b| b.is_ascii_digit()) => value,
+                    _ => return Err(self.error(&line,format!("expected an integer from 0 to {} , found '{}'",i32::MAX,line.text)))
+                };
+                Some(Expr::IntegerLiteral(Token::new(number,span,line.text.to_string(),TokenType::INTEGER,Some(Literal::INT(value)))))
+            },
+            "_string" =>{
+                let line = self.next(field,"a string")?;
+                let value = self.string(&line)?;
+                let lexeme = format!("\"{}\"",source_escape(&value));
+                Some(Expr::StringLiteral(Token::new(number,span,lexeme,TokenType::STRING,Some(Literal::STR(value.into())))))
+            },
+            "_bool" =>{
+                let line = self.next(field,"1 or 0")?;
+                let (value,tokentype) = match line.text{
+                    "1" => (true,TokenType::KEYTRUE),
+                    "0" => (false,TokenType::KEYFALSE),
+                    _ => return Err(self.error(&line,format!("expected 1 or 0 , found '{}'",line.text)))
+                };
+                Some(Expr::BoolLiteral(Token::new(number,span,value.to_string(),tokentype,Some(Literal::BOOL(value)))))
+            },
+            _ => return Err(self.error(&name,format!("unknown node '{}'",name.text)))
+        };
+        let type_ = self.next(indent,"the type of the expression like ': _no_type'")?;
+        if type_.text.strip_prefix(": ").is_none_or(|t| t.is_empty() || t.contains(' ')){
+            return Err(self.error(&type_,format!("expected the type of the expression like ': _no_type' , found '{}'",type_.text)));
+        }
+        Ok((name,expr))
+    }
+
+    //( the arguments of a dispatch )
+    fn arguments(&mut self,indent:usize)->Result<Vec<Expr>,Diagnostic>{
+        self.keyword(indent,"(")?;
+        let mut arguments = vec![];
+        while self.at_node(indent){
+            arguments.push(self.expr(indent)?);
+        }
+        self.keyword(indent,")")?;
+        Ok(arguments)
+    }
+
+    //a quoted string with the escapes of tokenprinter::escape , \ooo octal for every other byte
+    fn string(&self,line:&Line)->Result<String,Diagnostic>{
+        let invalid = |message:&str| self.error(line,format!("{} in string {}",message,line.text));
+        let Some(inner) = line.text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) else{
+            return Err(self.error(line,format!("expected a quoted string , found '{}'",line.text)));
+        };
+        let mut bytes = vec![];
+        let mut rest = inner.as_bytes();
+        while let Some((&byte,tail)) = rest.split_first(){
+            rest = tail;
+            if byte == b'"'{
+                return Err(invalid("unescaped '\"'"));
+            }
+            if byte != b'\\'{
+                bytes.push(byte);
+                continue;
+            }
+            let Some((&escape,tail)) = rest.split_first() else{
+                return Err(invalid("'\\' at the end"));
+            };
+            rest = tail;
+            bytes.push(match escape{
+                b'n' => b'\n',
+                b't' => b'\t',
+                b'b' => 0x08,
+                b'f' => 0x0c,
+                b'\\'|b'"' => escape,
+                b'0'..=b'3' if rest.len() >= 2 && rest[..2].iter().all(|d| (b'0'..=b'7').contains(d)) =>{
+                    let value = (escape - b'0') * 64 + (rest[0] - b'0') * 8 + (rest[1] - b'0');
+                    rest = &rest[2..];
+                    value
+                },
+                _ => return Err(invalid("unknown escape"))
+            });
+        }
+        String::from_utf8(bytes).map_err(|_| invalid("invalid utf-8"))
+    }
+}
+
+//a span that only knows its line , the dump has no columns or offsets
+fn span(line:usize)->Span{
+    Span::new(0,0,line,0)
+}
+
+fn operator(name:&str)->(TokenType,&'static str){
+    match name{
+        "_plus" => (TokenType::PLUS,"+"),
+        "_sub" => (TokenType::MINUS,"-"),
+        "_mul" => (TokenType::STAR,"*"),
+        "_divide" => (TokenType::SLASH,"/"),
+        "_lt" => (TokenType::LESSTHAN,"<"),
+        "_leq" => (TokenType::LESSEQUAL,"<="),
+        "_gt" => (TokenType::MORETHAN,">"),
+        "_geq" => (TokenType::MOREEQUAL,">="),
+        _ => (TokenType::EQUALITY,"=")
+    }
+}
+
+//the string as it would be written in cool source , the lexeme of the token
+fn source_escape(value:&str)->String{
+    let mut escaped = String::new();
+    for c in value.chars(){
+        match c{
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            _ => escaped.push(c)
+        }
+    }
+    escaped
+}
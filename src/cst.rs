@@ -0,0 +1,329 @@
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::token::*;
+
+/*
+lossless concrete syntax tree
+
+the parser can build it next to the ast (Parser::parse_cst) from a token stream that kept its trivia .
+every token of the source ends up in the tree , lexical errors and tokens skipped during error recovery
+included , so the text of the tree is the source it was parsed from , byte for byte.
+
+green nodes know their kind , their children and the length of their text , nothing about where they
+are . they can be shared and rebuilt cheaply . red nodes (SyntaxNode , SyntaxToken) are made on the fly
+while walking down from the root and add the offset and the parent .
+
+PROGRAM
+  CLASS
+    METHOD
+      FORMAL
+      BLOCK_EXPR
+        DISPATCH_EXPR
+          NAME_EXPR
+          ARG_LIST
+            LITERAL
+  ...
+
+the ; after a class or a feature belongs to the enclosing node , so the text range of a node without
+its trivia is the span the ast gives the same construct . lower.rs turns a tree into an ast::Program
+*/
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub enum SyntaxKind{
+    PROGRAM,
+    CLASS,
+    ATTRIBUTE,
+    METHOD,
+    FORMAL,
+    ASSIGN_EXPR,
+    BINARY_EXPR,
+    NOT_EXPR,
+    NEG_EXPR,
+    ISVOID_EXPR,
+    NEW_EXPR,
+    DELETE_EXPR,
+    DISPATCH_EXPR,
+    ARG_LIST,
+    IF_EXPR,
+    WHILE_EXPR,
+    BLOCK_EXPR,
+    LET_EXPR,
+    LET_BINDING,
+    CASE_EXPR,
+    CASE_BRANCH,
+    PAREN_EXPR,
+    NAME_EXPR,
+    LITERAL,
+    //tokens skipped while recovering from a syntax error , and expressions that could not be parsed
+    ERROR
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct GreenTrivia{
+    pub kind : TriviaKind,
+    pub text : String
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct GreenToken{
+    pub kind : TokenType,
+    pub text : String,
+    pub literal : Option<Literal>,
+    pub leading : Vec<GreenTrivia>,
+    pub trailing : Vec<GreenTrivia>
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct GreenNode{
+    kind : SyntaxKind,
+    width : usize,
+    children : Vec<GreenElement>
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub enum GreenElement{
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>)
+}
+
+impl GreenToken{
+    pub fn new(token:&Token)->GreenToken{
+        let trivia = |trivia:&Vec<Trivia>| trivia.iter().map(|t| GreenTrivia{kind:t.kind,text:t.text.clone()}).collect();
+        GreenToken{
+            kind:token.tokentype,
+            //the EOF token has no text of its own , it only carries the trivia at the end of the file
+            text:if token.tokentype == TokenType::EOF {String::new()} else {token.lexeme.clone()},
+            literal:token.literal.clone(),
+            leading:trivia(&token.leading),
+            trailing:trivia(&token.trailing)
+        }
+    }
+
+    fn leading_width(&self)->usize{
+        self.leading.iter().map(|t| t.text.len()).sum()
+    }
+
+    pub fn width(&self)->usize{
+        self.leading_width() + self.text.len() + self.trailing.iter().map(|t| t.text.len()).sum::<usize>()
+    }
+
+    fn write(&self,text:&mut String){
+        for trivia in self.leading.iter(){
+            text.push_str(&trivia.text);
+        }
+        text.push_str(&self.text);
+        for trivia in self.trailing.iter(){
+            text.push_str(&trivia.text);
+        }
+    }
+}
+
+impl GreenNode{
+    pub fn new(kind:SyntaxKind,children:Vec<GreenElement>)->GreenNode{
+        let width = children.iter().map(|c| c.width()).sum();
+        GreenNode{kind,width,children}
+    }
+
+    pub fn kind(&self)->SyntaxKind{
+        self.kind
+    }
+
+    pub fn width(&self)->usize{
+        self.width
+    }
+
+    pub fn children(&self)->&[GreenElement]{
+        &self.children
+    }
+
+    fn write(&self,text:&mut String){
+        for child in self.children.iter(){
+            match child{
+                GreenElement::Node(node) => node.write(text),
+                GreenElement::Token(token) => token.write(text)
+            }
+        }
+    }
+}
+
+impl GreenElement{
+    pub fn width(&self)->usize{
+        match self{
+            GreenElement::Node(node) => node.width,
+            GreenElement::Token(token) => token.width()
+        }
+    }
+}
+
+//builds the green tree bottom up . elements are pushed in source order , a node is made by wrapping
+//everything pushed since a checkpoint , which also works for left recursive rules like a + b + c
+#[derive(Debug,Default)]
+pub struct TreeBuilder{
+    elements : Vec<GreenElement>
+}
+
+impl TreeBuilder{
+    pub fn new()->TreeBuilder{
+        TreeBuilder{elements:vec![]}
+    }
+
+    pub fn token(&mut self,token:&Token){
+        self.elements.push(GreenElement::Token(Rc::new(GreenToken::new(token))));
+    }
+
+    pub fn checkpoint(&self)->usize{
+        self.elements.len()
+    }
+
+    pub fn finish_at(&mut self,checkpoint:usize,kind:SyntaxKind){
+        let children = self.elements.split_off(checkpoint.min(self.elements.len()));
+        self.elements.push(GreenElement::Node(Rc::new(GreenNode::new(kind,children))));
+    }
+
+    //the root , holding whatever was pushed
+    pub fn finish(self,kind:SyntaxKind)->GreenNode{
+        GreenNode::new(kind,self.elements)
+    }
+}
+
+#[derive(Debug)]
+struct NodeData{
+    green : Rc<GreenNode>,
+    offset : usize,
+    parent : Option<SyntaxNode>
+}
+
+#[derive(Debug,Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+#[derive(Debug,Clone)]
+pub struct SyntaxToken{
+    green : Rc<GreenToken>,
+    offset : usize,
+    parent : SyntaxNode
+}
+
+#[derive(Debug,Clone)]
+pub enum SyntaxElement{
+    Node(SyntaxNode),
+    Token(SyntaxToken)
+}
+
+impl SyntaxNode{
+    pub fn new_root(green:GreenNode)->SyntaxNode{
+        SyntaxNode(Rc::new(NodeData{green:Rc::new(green),offset:0,parent:None}))
+    }
+
+    pub fn kind(&self)->SyntaxKind{
+        self.0.green.kind
+    }
+
+    pub fn green(&self)->&GreenNode{
+        &self.0.green
+    }
+
+    pub fn parent(&self)->Option<SyntaxNode>{
+        self.0.parent.clone()
+    }
+
+    //byte range of the node , trivia included
+    pub fn text_range(&self)->Range<usize>{
+        self.0.offset..self.0.offset + self.0.green.width
+    }
+
+    pub fn text(&self)->String{
+        let mut text = String::new();
+        self.0.green.write(&mut text);
+        text
+    }
+
+    pub fn children_with_tokens(&self)->Vec<SyntaxElement>{
+        let mut offset = self.0.offset;
+        let mut children = vec![];
+        for child in self.0.green.children.iter(){
+            children.push(match child{
+                GreenElement::Node(green) => SyntaxElement::Node(SyntaxNode(Rc::new(NodeData{
+                    green:green.clone(),
+                    offset,
+                    parent:Some(self.clone())
+                }))),
+                GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken{green:green.clone(),offset,parent:self.clone()})
+            });
+            offset += child.width();
+        }
+        children
+    }
+
+    pub fn children(&self)->Vec<SyntaxNode>{
+        self.children_with_tokens().into_iter().filter_map(|c| match c{
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None
+        }).collect()
+    }
+
+    //tokens directly under this node
+    pub fn tokens(&self)->Vec<SyntaxToken>{
+        self.children_with_tokens().into_iter().filter_map(|c| match c{
+            SyntaxElement::Token(token) => Some(token),
+            SyntaxElement::Node(_) => None
+        }).collect()
+    }
+
+    //every token of the subtree in source order
+    pub fn descendant_tokens(&self)->Vec<SyntaxToken>{
+        let mut tokens = vec![];
+        for child in self.children_with_tokens(){
+            match child{
+                SyntaxElement::Node(node) => tokens.extend(node.descendant_tokens()),
+                SyntaxElement::Token(token) => tokens.push(token)
+            }
+        }
+        tokens
+    }
+
+    //byte range from the first to the last token , without the trivia around them
+    pub fn trimmed_range(&self)->Range<usize>{
+        let tokens = self.descendant_tokens();
+        match (tokens.first(),tokens.last()){
+            (Some(first),Some(last)) => first.text_range().start..last.text_range().end,
+            _ => self.0.offset..self.0.offset
+        }
+    }
+}
+
+impl SyntaxToken{
+    pub fn kind(&self)->TokenType{
+        self.green.kind
+    }
+
+    pub fn green(&self)->&GreenToken{
+        &self.green
+    }
+
+    pub fn parent(&self)->SyntaxNode{
+        self.parent.clone()
+    }
+
+    //the token itself , without trivia
+    pub fn text(&self)->&str{
+        &self.green.text
+    }
+
+    pub fn text_range(&self)->Range<usize>{
+        let start = self.offset + self.green.leading_width();
+        start..start + self.green.text.len()
+    }
+
+    pub fn literal(&self)->Option<&Literal>{
+        self.green.literal.as_ref()
+    }
+
+    pub fn leading(&self)->&[GreenTrivia]{
+        &self.green.leading
+    }
+
+    pub fn trailing(&self)->&[GreenTrivia]{
+        &self.green.trailing
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use crate::token::Span;
+
+/*
+diagnostics
+
+every phase reports problems as Diagnostic values pushed into a Diagnostics sink instead of printing them .
+the driver (or an embedder , or a test) decides what to do with them once the phase is done.
+
+codes are grouped by phase:
+E00xx scanner
+E01xx parser
+E02xx reader of ast dumps
+*/
+
+pub const INVALID_CHARACTER:&str = "E0001";
+pub const UNTERMINATED_STRING:&str = "E0002";
+pub const NULL_IN_STRING:&str = "E0003";
+pub const UNTERMINATED_COMMENT:&str = "E0004";
+pub const STRING_TOO_LONG:&str = "E0005";
+pub const INTEGER_OUT_OF_RANGE:&str = "E0006";
+pub const UNMATCHED_COMMENT:&str = "E0007";
+
+pub const EXPECTED_TOKEN:&str = "E0100";
+pub const RESERVED_NAME:&str = "E0101";
+pub const ILLEGAL_FEATURE:&str = "E0103";
+pub const EMPTY_BLOCK:&str = "E0104";
+pub const EMPTY_CASE:&str = "E0105";
+pub const INVALID_ASSIGNMENT:&str = "E0106";
+pub const INVALID_CALL:&str = "E0107";
+pub const EXPECTED_EXPRESSION:&str = "E0108";
+pub const NON_ASSOCIATIVE:&str = "E0109";
+
+pub const MALFORMED_AST:&str = "E0200";
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub enum Severity{
+    ERROR,
+    WARNING,
+    NOTE
+}
+
+impl fmt::Display for Severity{
+    fn fmt(&self,f:&mut fmt::Formatter)->fmt::Result{
+        match self{
+            Severity::ERROR => write!(f,"error"),
+            Severity::WARNING => write!(f,"warning"),
+            Severity::NOTE => write!(f,"note")
+        }
+    }
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct Diagnostic{
+    pub severity : Severity,
+    pub code : &'static str,
+    pub message : String,
+    pub span : Span,
+    pub notes : Vec<String>
+}
+
+impl Diagnostic{
+    pub fn new(severity:Severity,code:&'static str,message:String,span:Span)->Diagnostic{
+        Diagnostic{severity,code,message,span,notes:vec![]}
+    }
+
+    pub fn error(code:&'static str,message:String,span:Span)->Diagnostic{
+        Diagnostic::new(Severity::ERROR,code,message,span)
+    }
+
+    pub fn warning(code:&'static str,message:String,span:Span)->Diagnostic{
+        Diagnostic::new(Severity::WARNING,code,message,span)
+    }
+
+    pub fn with_note(mut self,note:String)->Diagnostic{
+        self.notes.push(note);
+        self
+    }
+}
+
+//one line summary , line:column: error[code]: message , followed by the notes
+impl fmt::Display for Diagnostic{
+    fn fmt(&self,f:&mut fmt::Formatter)->fmt::Result{
+        write!(f,"{}:{}: {}[{}]: {}",self.span.line,self.span.column,self.severity,self.code,self.message)?;
+        for note in self.notes.iter(){
+            write!(f,"\n  = note: {}",note)?;
+        }
+        Ok(())
+    }
+}
+
+//the sink every phase reports into
+#[derive(Debug,Clone,Default)]
+pub struct Diagnostics{
+    diagnostics : Vec<Diagnostic>
+}
+
+impl Diagnostics{
+    pub fn new()->Diagnostics{
+        Diagnostics{diagnostics:vec![]}
+    }
+
+    pub fn push(&mut self,diagnostic:Diagnostic){
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn extend(&mut self,other:Diagnostics){
+        self.diagnostics.extend(other.diagnostics);
+    }
+
+    pub fn has_errors(&self)->bool{
+        self.diagnostics.iter().any(|d| d.severity == Severity::ERROR)
+    }
+
+    pub fn len(&self)->usize{
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.diagnostics.is_empty()
+    }
+
+    pub fn iter(&self)->std::slice::Iter<'_,Diagnostic>{
+        self.diagnostics.iter()
+    }
+}
+
+impl IntoIterator for Diagnostics{
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+    fn into_iter(self)->Self::IntoIter{
+        self.diagnostics.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics{
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a,Diagnostic>;
+    fn into_iter(self)->Self::IntoIter{
+        self.diagnostics.iter()
+    }
+}
@@ -0,0 +1,630 @@
+use std::collections::{HashSet,VecDeque};
+
+use crate::cst::*;
+use crate::diagnostic::*;
+use crate::parser::Parser;
+use crate::scanner::Scanner;
+use crate::token::*;
+
+/*
+canonical formatter , cool-compiler-rs fmt
+
+the source is parsed into the lossless syntax tree and printed again from it , so comments survive and
+every token is printed exactly once . the layout is worked out with groups in the style of wadler's
+prettier printer : a group is printed on one line when it fits in the line length , otherwise its
+line breaks are taken and its contents are indented.
+
+class Main inherits IO {
+    x : Int <- 1;
+
+    main() : Object {
+        {
+            out_string("hi\n");
+            x <- x + 1;
+        }
+    };
+};
+
+blank lines between features , block expressions and case branches are kept (one at most) ,
+classes are always separated by one . parentheses are kept as written , so the formatted source
+parses to the same ast . formatting formatted source gives it back unchanged.
+*/
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub struct FormatOptions{
+    pub indent : usize,
+    pub width : usize
+}
+
+impl Default for FormatOptions{
+    fn default()->FormatOptions{
+        FormatOptions{indent:4,width:80}
+    }
+}
+
+//the formatted source , or the errors that kept it from being parsed
+pub fn format(source:&str,options:&FormatOptions)->Result<String,Diagnostics>{
+    let mut scanner = Scanner::new(source.to_string()).with_trivia();
+    let (root,mut diagnostics) = Parser::new(&mut scanner).parse_cst();
+    let lexical = scanner.take_diagnostics();
+    if lexical.has_errors() || diagnostics.has_errors(){
+        let mut errors = lexical;
+        errors.extend(std::mem::take(&mut diagnostics));
+        return Err(errors);
+    }
+    let mut formatter = Formatter{indent:options.indent,handled:HashSet::new()};
+    let doc = formatter.program(&root);
+    Ok(Printer::new(options.width).print(&doc))
+}
+
+#[derive(Debug,Clone)]
+enum Doc{
+    TEXT(String),
+    //a space when the group is flat , a line break otherwise
+    LINE,
+    //nothing when the group is flat , a line break otherwise
+    SOFTLINE,
+    //always a line break , the groups around it can not be flat
+    HARDLINE,
+    //ends the line after a -- comment , unless a line break comes next anyway
+    COMMENTBREAK,
+    NEST(usize,Box<Doc>),
+    GROUP(Box<Doc>),
+    CONCAT(Vec<Doc>)
+}
+
+fn text(text:&str)->Doc{
+    Doc::TEXT(text.to_string())
+}
+
+fn concat(docs:Vec<Doc>)->Doc{
+    Doc::CONCAT(docs)
+}
+
+fn group(doc:Doc)->Doc{
+    Doc::GROUP(Box::new(doc))
+}
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+enum Blank{
+    NEVER,
+    KEEP,
+    ALWAYS
+}
+
+struct Formatter{
+    indent : usize,
+    //tokens whose leading comments were already printed as lines of their own
+    handled : HashSet<usize>
+}
+
+fn first_token(node:&SyntaxNode)->Option<SyntaxToken>{
+    node.descendant_tokens().into_iter().next()
+}
+
+fn is_comment(trivia:&GreenTrivia)->bool{
+    matches!(trivia.kind,TriviaKind::LINECOMMENT|TriviaKind::BLOCKCOMMENT)
+}
+
+fn comment_text(trivia:&GreenTrivia)->String{
+    match trivia.kind{
+        TriviaKind::LINECOMMENT => trivia.text.trim_end().to_string(),
+        _ => trivia.text.clone()
+    }
+}
+
+impl Formatter{
+    fn nest(&self,doc:Doc)->Doc{
+        Doc::NEST(self.indent,Box::new(doc))
+    }
+
+    //a token with the comments around it
+    fn token(&mut self,token:&SyntaxToken)->Doc{
+        let mut docs = vec![];
+        if !self.handled.contains(&token.text_range().start){
+            let leading = token.leading();
+            for (i,trivia) in leading.iter().enumerate(){
+                if !is_comment(trivia){
+                    continue;
+                }
+                docs.push(Doc::TEXT(comment_text(trivia)));
+                let line_ends = trivia.kind == TriviaKind::LINECOMMENT ||
+                    leading[i + 1..].iter().take_while(|t| !is_comment(t)).any(|t| t.kind == TriviaKind::NEWLINE);
+                docs.push(if line_ends {Doc::COMMENTBREAK} else {text(" ")});
+            }
+        }
+        docs.push(text(token.text()));
+        for trivia in token.trailing().iter().filter(|t| is_comment(t)){
+            docs.push(text(" "));
+            docs.push(Doc::TEXT(comment_text(trivia)));
+            if trivia.kind == TriviaKind::LINECOMMENT{
+                docs.push(Doc::COMMENTBREAK);
+            }
+        }
+        concat(docs)
+    }
+
+    //line break before something that starts a line of its own , a feature , a block expression or a case
+    //branch , with the comments on the lines above it and the blank lines between them
+    fn lines_before(&mut self,token:&SyntaxToken,separator:Doc,blank:Blank)->Doc{
+        self.handled.insert(token.text_range().start);
+        let mut docs = vec![separator];
+        let mut newlines = 0;
+        let mut first = true;
+        //NEVER only holds before the first comment , the blank lines after it are kept
+        let blank_line = |first:bool,newlines:usize| match blank{
+            Blank::NEVER => !first && newlines >= 2,
+            Blank::KEEP => newlines >= 2,
+            Blank::ALWAYS => first || newlines >= 2
+        };
+        for trivia in token.leading(){
+            match trivia.kind{
+                TriviaKind::NEWLINE => newlines += 1,
+                TriviaKind::WHITESPACE => (),
+                _ =>{
+                    if blank_line(first,newlines){
+                        docs.push(Doc::HARDLINE);
+                    }
+                    docs.push(Doc::TEXT(comment_text(trivia)));
+                    docs.push(Doc::HARDLINE);
+                    newlines = 0;
+                    first = false;
+                }
+            }
+        }
+        if blank_line(first,newlines){
+            docs.push(Doc::HARDLINE);
+        }
+        concat(docs)
+    }
+
+    //comments before a closing token , printed on lines of their own inside the body
+    fn dangling(&mut self,token:&SyntaxToken)->Doc{
+        self.handled.insert(token.text_range().start);
+        let mut docs = vec![];
+        let mut newlines = 0;
+        for trivia in token.leading(){
+            match trivia.kind{
+                TriviaKind::NEWLINE => newlines += 1,
+                TriviaKind::WHITESPACE => (),
+                _ =>{
+                    if newlines >= 2 && !docs.is_empty(){
+                        docs.push(Doc::HARDLINE);
+                    }
+                    docs.push(Doc::HARDLINE);
+                    docs.push(Doc::TEXT(comment_text(trivia)));
+                    newlines = 0;
+                }
+            }
+        }
+        concat(docs)
+    }
+
+    fn program(&mut self,root:&SyntaxNode)->Doc{
+        let mut docs = vec![];
+        let mut first = true;
+        for element in root.children_with_tokens(){
+            match element{
+                SyntaxElement::Node(class) =>{
+                    if let Some(token) = first_token(&class){
+                        let separator = if first {concat(vec![])} else {Doc::HARDLINE};
+                        docs.push(self.lines_before(&token,separator,if first {Blank::NEVER} else {Blank::ALWAYS}));
+                    }
+                    docs.push(self.class(&class));
+                    first = false;
+                },
+                SyntaxElement::Token(token) if token.kind() == TokenType::EOF =>{
+                    let comments = self.dangling(&token);
+                    //comments at the top of an empty file have no line before them
+                    match (first,comments){
+                        (true,Doc::CONCAT(mut comments)) if !comments.is_empty() =>{
+                            comments.remove(0);
+                            docs.push(concat(comments));
+                        },
+                        (_,comments) => docs.push(comments)
+                    }
+                    docs.push(self.token(&token));
+                    if !first || token.leading().iter().any(is_comment){
+                        docs.push(Doc::HARDLINE);
+                    }
+                },
+                SyntaxElement::Token(token) => docs.push(self.token(&token))
+            }
+        }
+        concat(docs)
+    }
+
+    fn class(&mut self,node:&SyntaxNode)->Doc{
+        let mut items : VecDeque<SyntaxElement> = node.children_with_tokens().into();
+        let mut docs = vec![];
+        //class Name [inherits Parent] {
+        while let Some(SyntaxElement::Token(token)) = items.pop_front(){
+            if !docs.is_empty(){
+                docs.push(text(" "));
+            }
+            docs.push(self.token(&token));
+            if token.kind() == TokenType::LEFTBRACE{
+                break;
+            }
+        }
+        let mut body = vec![];
+        let mut first = true;
+        while let Some(item) = items.pop_front(){
+            match item{
+                SyntaxElement::Node(feature) =>{
+                    if let Some(token) = first_token(&feature){
+                        body.push(self.lines_before(&token,Doc::HARDLINE,if first {Blank::NEVER} else {Blank::KEEP}));
+                    }
+                    body.push(self.feature(&feature));
+                    first = false;
+                },
+                SyntaxElement::Token(token) if token.kind() == TokenType::RIGHTBRACE =>{
+                    body.push(self.dangling(&token));
+                    let empty = first && token.leading().iter().all(|t| !is_comment(t));
+                    docs.push(self.nest(concat(body)));
+                    if !empty{
+                        docs.push(Doc::HARDLINE);
+                    }
+                    docs.push(self.token(&token));
+                    break;
+                },
+                SyntaxElement::Token(token) => body.push(self.token(&token))
+            }
+        }
+        concat(docs)
+    }
+
+    fn feature(&mut self,node:&SyntaxNode)->Doc{
+        match node.kind(){
+            SyntaxKind::METHOD => self.method(node),
+            _ => self.binding(node)
+        }
+    }
+
+    //an attribute , a formal , a let binding : name : Type [<- init]
+    fn binding(&mut self,node:&SyntaxNode)->Doc{
+        let mut docs = vec![];
+        for element in node.children_with_tokens(){
+            match element{
+                SyntaxElement::Token(token) if token.kind() == TokenType::OBJECTID => docs.push(self.token(&token)),
+                SyntaxElement::Token(token) =>{
+                    docs.push(text(" "));
+                    docs.push(self.token(&token));
+                },
+                SyntaxElement::Node(init) =>{
+                    let init = self.expr(&init);
+                    docs.push(group(self.nest(concat(vec![Doc::LINE,init]))));
+                }
+            }
+        }
+        concat(docs)
+    }
+
+    fn method(&mut self,node:&SyntaxNode)->Doc{
+        let mut docs = vec![];
+        let mut header = true;
+        let mut body = vec![];
+        for element in node.children_with_tokens(){
+            match element{
+                SyntaxElement::Node(formal) if formal.kind() == SyntaxKind::FORMAL => docs.push(self.binding(&formal)),
+                SyntaxElement::Node(expr) => body.push(self.expr(&expr)),
+                SyntaxElement::Token(token) if header =>{
+                    match token.kind(){
+                        TokenType::COMMA|TokenType::COLON|TokenType::TYPEID =>{
+                            if token.kind() != TokenType::COMMA{
+                                docs.push(text(" "));
+                            }
+                            docs.push(self.token(&token));
+                            if token.kind() == TokenType::COMMA{
+                                docs.push(text(" "));
+                            }
+                        },
+                        TokenType::LEFTBRACE =>{
+                            docs.push(text(" "));
+                            body.push(self.token(&token));
+                            header = false;
+                        },
+                        _ => docs.push(self.token(&token))
+                    }
+                },
+                SyntaxElement::Token(token) =>{
+                    let close = self.token(&token);
+                    let expr = body.pop().unwrap_or(concat(vec![]));
+                    let open = body.pop().unwrap_or(concat(vec![]));
+                    docs.push(group(concat(vec![open,self.nest(concat(vec![Doc::LINE,expr])),Doc::LINE,close])));
+                }
+            }
+        }
+        concat(docs)
+    }
+
+    //the tokens and child expressions of node in order
+    fn items(node:&SyntaxNode)->VecDeque<SyntaxElement>{
+        node.children_with_tokens().into()
+    }
+
+    //the next token or child expression , printed
+    fn next(&mut self,items:&mut VecDeque<SyntaxElement>)->Doc{
+        match items.pop_front(){
+            Some(SyntaxElement::Token(token)) => self.token(&token),
+            Some(SyntaxElement::Node(node)) => self.expr(&node),
+            None => concat(vec![])
+        }
+    }
+
+    fn expr(&mut self,node:&SyntaxNode)->Doc{
+        let mut items = Self::items(node);
+        match node.kind(){
+            SyntaxKind::ASSIGN_EXPR =>{
+                let name = self.next(&mut items);
+                let arrow = self.next(&mut items);
+                let value = self.next(&mut items);
+                concat(vec![name,text(" "),arrow,group(self.nest(concat(vec![Doc::LINE,value])))])
+            },
+            SyntaxKind::BINARY_EXPR =>{
+                let left = self.next(&mut items);
+                let operator = self.next(&mut items);
+                let right = self.next(&mut items);
+                group(concat(vec![left,text(" "),operator,self.nest(concat(vec![Doc::LINE,right]))]))
+            },
+            SyntaxKind::NEG_EXPR|SyntaxKind::PAREN_EXPR|SyntaxKind::NAME_EXPR|SyntaxKind::LITERAL|SyntaxKind::DISPATCH_EXPR =>{
+                let mut docs = vec![];
+                while !items.is_empty(){
+                    docs.push(self.next(&mut items));
+                }
+                concat(docs)
+            },
+            SyntaxKind::NOT_EXPR|SyntaxKind::ISVOID_EXPR|SyntaxKind::DELETE_EXPR|SyntaxKind::NEW_EXPR =>{
+                let keyword = self.next(&mut items);
+                let operand = self.next(&mut items);
+                concat(vec![keyword,text(" "),operand])
+            },
+            SyntaxKind::ARG_LIST =>{
+                let open = self.next(&mut items);
+                let mut arguments = vec![];
+                while items.len() > 1{
+                    arguments.push(self.next(&mut items));
+                    if items.len() > 1{
+                        arguments.push(self.next(&mut items));
+                        arguments.push(Doc::LINE);
+                    }
+                }
+                let close = self.next(&mut items);
+                if arguments.is_empty(){
+                    return concat(vec![open,close]);
+                }
+                group(concat(vec![open,self.nest(concat(vec![Doc::SOFTLINE,concat(arguments)])),Doc::SOFTLINE,close]))
+            },
+            SyntaxKind::IF_EXPR => group(concat(self.if_chain(&mut items))),
+            SyntaxKind::WHILE_EXPR =>{
+                let mut docs = vec![];
+                for part in 0..5{
+                    let doc = self.next(&mut items);
+                    docs.push(match part{
+                        1 => concat(vec![text(" "),doc,text(" ")]),
+                        3 => self.nest(concat(vec![Doc::LINE,doc])),
+                        4 => concat(vec![Doc::LINE,doc]),
+                        _ => doc
+                    });
+                }
+                group(concat(docs))
+            },
+            SyntaxKind::LET_EXPR =>{
+                let keyword = self.next(&mut items);
+                let mut bindings = vec![];
+                while let Some(SyntaxElement::Node(binding)) = items.front().cloned(){
+                    items.pop_front();
+                    bindings.push(self.binding(&binding));
+                    if matches!(items.front(),Some(SyntaxElement::Token(t)) if t.kind() == TokenType::COMMA){
+                        bindings.push(self.next(&mut items));
+                        bindings.push(Doc::LINE);
+                    }
+                }
+                let in_ = self.next(&mut items);
+                let body = self.next(&mut items);
+                group(concat(vec![
+                    keyword,text(" "),
+                    group(self.nest(concat(bindings))),
+                    text(" "),in_,
+                    self.nest(concat(vec![Doc::LINE,body]))
+                ]))
+            },
+            SyntaxKind::BLOCK_EXPR => self.body(&mut items,true),
+            SyntaxKind::CASE_EXPR =>{
+                let keyword = self.next(&mut items);
+                let expr = self.next(&mut items);
+                let of = self.next(&mut items);
+                let body = self.body(&mut items,false);
+                group(concat(vec![keyword,text(" "),expr,text(" "),of,body]))
+            },
+            SyntaxKind::CASE_BRANCH =>{
+                let mut docs = vec![];
+                while let Some(item) = items.pop_front(){
+                    let semicolon = matches!(&item,SyntaxElement::Token(t) if t.kind() == TokenType::SEMICOLON);
+                    if !docs.is_empty() && !semicolon{
+                        docs.push(text(" "));
+                    }
+                    docs.push(match item{
+                        SyntaxElement::Token(token) => self.token(&token),
+                        SyntaxElement::Node(expr) => self.expr(&expr)
+                    });
+                }
+                concat(docs)
+            },
+            _ => text(&node.text())
+        }
+    }
+
+    //if ... then ... else if ... then ... else ... fi fi , the ifs in the else branches are printed as
+    //one chain that breaks all together
+    fn if_chain(&mut self,items:&mut VecDeque<SyntaxElement>)->Vec<Doc>{
+        let mut docs = vec![];
+        for part in 0..7{
+            if part == 5{
+                if let Some(SyntaxElement::Node(else_)) = items.front().cloned(){
+                    if else_.kind() == SyntaxKind::IF_EXPR{
+                        items.pop_front();
+                        docs.push(text(" "));
+                        docs.extend(self.if_chain(&mut Self::items(&else_)));
+                        docs.push(text(" "));
+                        docs.push(self.next(items));
+                        return docs;
+                    }
+                }
+            }
+            let doc = self.next(items);
+            docs.push(match part{
+                1 => concat(vec![text(" "),doc,text(" ")]),
+                3|5 => self.nest(concat(vec![Doc::LINE,doc])),
+                4|6 => concat(vec![Doc::LINE,doc]),
+                _ => doc
+            });
+        }
+        docs
+    }
+
+    //the lines of a block , { expr; ... } , or of a case , branch ... esac . the opening brace is still
+    //in items for a block
+    fn body(&mut self,items:&mut VecDeque<SyntaxElement>,block:bool)->Doc{
+        let open = if block {self.next(items)} else {concat(vec![])};
+        let mut lines = vec![];
+        let mut first = true;
+        while let Some(item) = items.pop_front(){
+            match item{
+                SyntaxElement::Node(expr) =>{
+                    if let Some(token) = first_token(&expr){
+                        lines.push(self.lines_before(&token,Doc::LINE,if first {Blank::NEVER} else {Blank::KEEP}));
+                    }
+                    lines.push(self.expr(&expr));
+                    first = false;
+                },
+                SyntaxElement::Token(token) if items.is_empty() =>{
+                    lines.push(self.dangling(&token));
+                    let close = self.token(&token);
+                    return group(concat(vec![open,self.nest(concat(lines)),Doc::LINE,close]));
+                },
+                SyntaxElement::Token(token) => lines.push(self.token(&token))
+            }
+        }
+        group(concat(vec![open,self.nest(concat(lines))]))
+    }
+}
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+enum Mode{
+    FLAT,
+    BREAK
+}
+
+struct Printer{
+    width : usize,
+    output : String,
+    column : usize,
+    //indentation still to be written for the line just started , written with the first text so blank
+    //lines stay empty
+    indent : Option<usize>,
+    //a -- comment was printed , the next text goes on a new line
+    comment_break : bool
+}
+
+fn has_text(doc:&Doc)->bool{
+    match doc{
+        Doc::TEXT(text) => !text.trim().is_empty(),
+        Doc::NEST(_,doc)|Doc::GROUP(doc) => has_text(doc),
+        Doc::CONCAT(docs) => docs.iter().any(has_text),
+        _ => false
+    }
+}
+
+impl Printer{
+    fn new(width:usize)->Printer{
+        Printer{width,output:String::new(),column:0,indent:None,comment_break:false}
+    }
+
+    fn print(mut self,doc:&Doc)->String{
+        let mut stack : Vec<(usize,Mode,&Doc)> = vec![(0,Mode::BREAK,doc)];
+        while let Some((indent,mode,doc)) = stack.pop(){
+            match doc{
+                Doc::TEXT(text) => self.text(text,indent),
+                Doc::CONCAT(docs) => stack.extend(docs.iter().rev().map(|d| (indent,mode,d))),
+                Doc::NEST(width,doc) => stack.push((indent + width,mode,doc)),
+                Doc::GROUP(doc) =>{
+                    let flat = mode == Mode::FLAT || self.fits((indent,Mode::FLAT,doc),&stack);
+                    stack.push((indent,if flat {Mode::FLAT} else {Mode::BREAK},doc));
+                },
+                Doc::LINE if mode == Mode::FLAT => self.text(" ",indent),
+                Doc::SOFTLINE if mode == Mode::FLAT => (),
+                Doc::LINE|Doc::SOFTLINE|Doc::HARDLINE => self.newline(indent),
+                Doc::COMMENTBREAK => self.comment_break = true
+            }
+        }
+        self.output
+    }
+
+    fn newline(&mut self,indent:usize){
+        self.comment_break = false;
+        self.output.push('\n');
+        self.column = 0;
+        self.indent = Some(indent);
+    }
+
+    fn text(&mut self,text:&str,indent:usize){
+        //no spaces at the start or the end of a line
+        if text.trim().is_empty() && (self.comment_break || self.indent.is_some()){
+            return;
+        }
+        if self.comment_break{
+            self.newline(indent);
+        }
+        if let Some(indent) = self.indent.take(){
+            self.output.push_str(&" ".repeat(indent));
+            self.column = indent;
+        }
+        self.output.push_str(text);
+        match text.rfind('\n'){
+            Some(i) => self.column = text[i + 1..].chars().count(),
+            None => self.column += text.chars().count()
+        }
+    }
+
+    //whether next fits flat on the rest of the line , what comes after it up to the next line break
+    //included
+    fn fits(&self,next:(usize,Mode,&Doc),rest:&[(usize,Mode,&Doc)])->bool{
+        let mut width = self.width as isize - self.column.max(self.indent.unwrap_or(0)) as isize;
+        let mut stack = vec![next];
+        let mut rest = rest.iter().rev();
+        let mut in_rest = false;
+        loop{
+            let (indent,mode,doc) = match stack.pop(){
+                Some(item) => item,
+                None => match rest.next(){
+                    Some(item) =>{
+                        in_rest = true;
+                        *item
+                    },
+                    None => return true
+                }
+            };
+            match doc{
+                Doc::TEXT(text) =>{
+                    if let Some(i) = text.find('\n'){
+                        return in_rest && width >= text[..i].chars().count() as isize;
+                    }
+                    width -= text.chars().count() as isize;
+                    if width < 0{
+                        return false;
+                    }
+                },
+                Doc::CONCAT(docs) => stack.extend(docs.iter().rev().map(|d| (indent,mode,d))),
+                Doc::NEST(width,doc) => stack.push((indent + width,mode,doc)),
+                Doc::GROUP(doc) => stack.push((indent,mode,doc)),
+                Doc::LINE if mode == Mode::FLAT => width -= 1,
+                Doc::SOFTLINE if mode == Mode::FLAT => (),
+                Doc::LINE|Doc::SOFTLINE => return true,
+                Doc::HARDLINE => return mode == Mode::BREAK,
+                //a comment at the end of the group ends the line , anything after it does not fit
+                Doc::COMMENTBREAK => return in_rest || !stack.iter().any(|(_,_,d)| has_text(d))
+            }
+        }
+    }
+}
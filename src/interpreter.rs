@@ -0,0 +1,533 @@
+use std::collections::HashMap;
+use std::io::{BufRead,Write};
+
+use crate::ast::*;
+use crate::semant::*;
+use crate::token::{Token,TokenType,Span};
+
+/*
+tree walking interpreter
+
+runs a program that passed semantic analysis by evaluating the ast directly , starting with
+(new Main).main() . Int , Bool and String are plain values , every other object lives on the heap
+and is referred to by its index , so assignment and argument passing share objects the same way
+pointers do in the generated code.
+
+runtime errors follow the cool manual and the spim runtime:
+dispatch on void , case on void , case without a matching branch , division by zero ,
+substr out of range and abort all stop the program.
+*/
+
+//deep enough for every example , shallow enough to fail before the native stack does
+const MAX_CALL_DEPTH:usize = 10000;
+
+#[derive(Debug,Clone,PartialEq)]
+pub enum Value{
+    Void,
+    Int(i32),
+    Bool(bool),
+    Str(String),
+    Object(usize)//index into the heap
+}
+
+#[derive(Debug)]
+struct Object{
+    class : String,
+    attributes : HashMap<String,Value>
+}
+
+#[derive(Debug)]
+pub struct RuntimeError{
+    pub filename : String,
+    pub line : usize,
+    pub message : String
+}
+
+impl RuntimeError{
+    pub fn new(filename:&str,line:usize,message:String)->RuntimeError{
+        RuntimeError{filename:filename.to_string(),line,message}
+    }
+
+    pub fn report(&self)->String{
+        if self.line == 0{
+            self.message.clone()
+        }
+        else{
+            format!("{}:{}: {}",self.filename,self.line,self.message)
+        }
+    }
+}
+
+type Eval = Result<Value,RuntimeError>;
+
+struct Frame<'a>{
+    self_value : Value,
+    filename : &'a str,//file of the class the running code belongs to
+    scopes : Vec<HashMap<String,Value>>
+}
+
+pub struct Interpreter<'a>{
+    table : &'a ClassTable,
+    classes : HashMap<&'a str,&'a Class>,
+    heap : Vec<Object>,
+    frames : Vec<Frame<'a>>,
+    input : Box<dyn BufRead + 'a>,
+    output : Box<dyn Write + 'a>
+}
+
+//runs Main.main on the standard input and output
+pub fn run(program:&Program,table:&ClassTable)->Result<(),RuntimeError>{
+    let stdin = std::io::stdin();
+    let mut interpreter = Interpreter::new(program,table,Box::new(stdin.lock()),Box::new(std::io::stdout()));
+    interpreter.run()
+}
+
+impl<'a> Interpreter<'a>{
+
+    pub fn new(program:&'a Program,table:&'a ClassTable,input:Box<dyn BufRead + 'a>,output:Box<dyn Write + 'a>)->Interpreter<'a>{
+        let mut classes = HashMap::new();
+        for class in program.classes(){
+            classes.entry(class.type_().lexeme.as_str()).or_insert(class);
+        }
+        Interpreter{
+            table,
+            classes,
+            heap:vec![],
+            frames:vec![],
+            input,
+            output
+        }
+    }
+
+    pub fn run(&mut self)->Result<(),RuntimeError>{
+        let main = self.instantiate(MAIN,0)?;
+        let method = Token::new(0,Span::default(),"main".to_string(),TokenType::OBJECTID,None);
+        let result = self.call(main,MAIN,&method,vec![]);
+        self.output.flush().ok();
+        result.map(|_| ())
+    }
+
+    fn frame(&mut self)->&mut Frame<'a>{
+        self.frames.last_mut().unwrap()
+    }
+
+    fn class_of(&self,value:&Value)->String{
+        match value{
+            Value::Int(_) => INT.to_string(),
+            Value::Bool(_) => BOOL.to_string(),
+            Value::Str(_) => STRING.to_string(),
+            Value::Object(index) => self.heap[*index].class.clone(),
+            Value::Void => OBJECT.to_string()
+        }
+    }
+
+    fn default_value(type_:&str)->Value{
+        match type_{
+            INT => Value::Int(0),
+            BOOL => Value::Bool(false),
+            STRING => Value::Str(String::new()),
+            _ => Value::Void
+        }
+    }
+
+    //allocates the object with default attributes , then runs the initializers from Object down
+    fn instantiate(&mut self,class:&str,line:usize)->Eval{
+        match class{
+            INT|BOOL|STRING => return Ok(Self::default_value(class)),
+            _ => ()
+        }
+        let attributes = self.table.all_attributes(class).iter()
+            .map(|a| (a.name.clone(),Self::default_value(&a.type_)))
+            .collect();
+        self.heap.push(Object{class:class.to_string(),attributes});
+        let object = Value::Object(self.heap.len() - 1);
+        let ancestors : Vec<String> = self.table.ancestors(class).iter().rev().map(|c| c.to_string()).collect();
+        for ancestor in ancestors{
+            let definition = match self.classes.get(ancestor.as_str()){
+                Some(definition) => *definition,
+                None => continue
+            };
+            self.push_frame(object.clone(),definition.filename(),line)?;
+            for feature in definition.features(){
+                if let Feature::Attribute{id,expr:Some(expr),..} = feature{
+                    let value = match expr.accept(self){
+                        Ok(value) => value,
+                        Err(e) =>{
+                            self.frames.pop();
+                            return Err(e);
+                        }
+                    };
+                    self.set_attribute(&object,&id.lexeme,value);
+                }
+            }
+            self.frames.pop();
+        }
+        Ok(object)
+    }
+
+    fn push_frame(&mut self,self_value:Value,filename:&'a str,line:usize)->Result<(),RuntimeError>{
+        if self.frames.len() >= MAX_CALL_DEPTH{
+            return Err(self.error(line,"Stack overflow.".to_string()));
+        }
+        self.frames.push(Frame{self_value,filename,scopes:vec![HashMap::new()]});
+        Ok(())
+    }
+
+    //an error in the code that is running right now
+    fn error(&self,line:usize,message:String)->RuntimeError{
+        let filename = self.frames.last().map_or("",|f| f.filename);
+        RuntimeError::new(filename,line,message)
+    }
+
+    fn set_attribute(&mut self,object:&Value,name:&str,value:Value){
+        if let Value::Object(index) = object{
+            self.heap[*index].attributes.insert(name.to_string(),value);
+        }
+    }
+
+    fn lookup(&mut self,id:&str)->Value{
+        let frame = self.frames.last().unwrap();
+        if id == "self"{
+            return frame.self_value.clone();
+        }
+        if let Some(value) = frame.scopes.iter().rev().find_map(|scope| scope.get(id)){
+            return value.clone();
+        }
+        match &frame.self_value{
+            Value::Object(index) => self.heap[*index].attributes.get(id).cloned().unwrap_or(Value::Void),
+            _ => Value::Void
+        }
+    }
+
+    fn assign(&mut self,id:&str,value:Value){
+        let frame = self.frames.last_mut().unwrap();
+        if let Some(scope) = frame.scopes.iter_mut().rev().find(|scope| scope.contains_key(id)){
+            scope.insert(id.to_string(),value);
+            return;
+        }
+        let self_value = frame.self_value.clone();
+        self.set_attribute(&self_value,id,value);
+    }
+
+    fn bind(&mut self,id:&str,value:Value){
+        let mut scope = HashMap::new();
+        scope.insert(id.to_string(),value);
+        self.frame().scopes.push(scope);
+    }
+
+    fn int(&mut self,expr:&Expr)->Result<i32,RuntimeError>{
+        match expr.accept(self)?{
+            Value::Int(value) => Ok(value),
+            _ => Ok(0)
+        }
+    }
+
+    fn bool(&mut self,expr:&Expr)->Result<bool,RuntimeError>{
+        match expr.accept(self)?{
+            Value::Bool(value) => Ok(value),
+            _ => Ok(false)
+        }
+    }
+
+    //finds the method in the class or its ancestors , user methods win over the basic ones
+    fn call(&mut self,receiver:Value,class:&str,method:&Token,arguments:Vec<Value>)->Eval{
+        for ancestor in self.table.ancestors(class){
+            let class = self.classes.get(ancestor).copied();
+            let definition = class.and_then(|c| c.features().iter().find(|f|
+                matches!(f,Feature::Method{id,..} if id.lexeme == method.lexeme)));
+            if let (Some(class),Some(Feature::Method{parameters,body,..})) = (class,definition){
+                self.push_frame(receiver,class.filename(),method.line)?;
+                for (parameter,argument) in parameters.iter().zip(arguments){
+                    self.frame().scopes[0].insert(parameter.id().lexeme.clone(),argument);
+                }
+                let result = body.accept(self);
+                self.frames.pop();
+                return result;
+            }
+            if BASIC_CLASSES.contains(&ancestor) && self.table.get(ancestor).unwrap().methods.iter().any(|m| m.name == method.lexeme){
+                return self.call_basic(receiver,&method.lexeme,arguments,method.line);
+            }
+        }
+        Err(self.error(method.line,format!("Dispatch to undefined method {}.",method.lexeme)))
+    }
+
+    fn call_basic(&mut self,receiver:Value,method:&str,arguments:Vec<Value>,line:usize)->Eval{
+        let mut arguments = arguments.into_iter();
+        let mut next_int = || match arguments.next(){
+            Some(Value::Int(value)) => value,
+            _ => 0
+        };
+        match method{
+            "abort" =>{
+                self.output.flush().ok();
+                Err(self.error(0,format!("Abort called from class {}",self.class_of(&receiver))))
+            }
+            "type_name" => Ok(Value::Str(self.class_of(&receiver))),
+            "copy" => match receiver{
+                Value::Object(index) =>{
+                    let object = Object{
+                        class:self.heap[index].class.clone(),
+                        attributes:self.heap[index].attributes.clone()
+                    };
+                    self.heap.push(object);
+                    Ok(Value::Object(self.heap.len() - 1))
+                }
+                value => Ok(value)
+            },
+            "out_string" =>{
+                if let Some(Value::Str(value)) = arguments.next(){
+                    write!(self.output,"{}",value).ok();
+                }
+                Ok(receiver)
+            }
+            "out_int" =>{
+                let value = next_int();
+                write!(self.output,"{}",value).ok();
+                Ok(receiver)
+            }
+            "in_string" => Ok(Value::Str(self.read_line())),
+            "in_int" => Ok(Value::Int(self.read_line().trim().parse().unwrap_or(0))),
+            "length" => match receiver{
+                Value::Str(value) => Ok(Value::Int(value.len() as i32)),
+                _ => Ok(Value::Int(0))
+            },
+            "concat" => match (receiver,arguments.next()){
+                (Value::Str(value),Some(Value::Str(other))) => Ok(Value::Str(value + &other)),
+                (receiver,_) => Ok(receiver)
+            },
+            "substr" =>{
+                let (start,length) = (next_int(),next_int());
+                let value = match receiver{
+                    Value::Str(value) => value,
+                    _ => String::new()
+                };
+                let end = match start.checked_add(length){
+                    Some(end) if start >= 0 && length >= 0 && end as usize <= value.len() => end,
+                    _ => return Err(self.error(line,"Index to substr is out of range".to_string()))
+                };
+                let bytes = &value.as_bytes()[start as usize..end as usize];
+                Ok(Value::Str(String::from_utf8_lossy(bytes).into_owned()))
+            }
+            _ => Err(self.error(line,format!("Dispatch to undefined method {}.",method)))
+        }
+    }
+
+    fn read_line(&mut self)->String{
+        self.output.flush().ok();
+        let mut line = String::new();
+        self.input.read_line(&mut line).ok();
+        line.trim_end_matches(['\n','\r']).to_string()
+    }
+}
+
+impl Visitor<Eval> for Interpreter<'_>{
+
+    fn visit_program(&mut self,_classes:&Vec<Class>)->Eval{
+        self.run().map(|_| Value::Void)
+    }
+
+    fn visit_class(&mut self,type_:&Token,_inherits:&Option<Token>,_features:&Vec<Feature>)->Eval{
+        self.instantiate(&type_.lexeme,type_.line)
+    }
+
+    fn visit_method(&mut self,_id:&Token,_type_:&Token,_parameters:&Vec<Formal>,body:&Expr)->Eval{
+        body.accept(self)
+    }
+
+    fn visit_attribute(&mut self,_id:&Token,_type_:&Token,expr:&Option<Expr>)->Eval{
+        match expr{
+            Some(expr) => expr.accept(self),
+            None => Ok(Value::Void)
+        }
+    }
+
+    fn visit_formal(&mut self,id:&Token,_type_:&Token)->Eval{
+        Ok(self.lookup(&id.lexeme))
+    }
+
+    fn visit_expr(&mut self,expr:&Expr)->Eval{
+        expr.accept(self)
+    }
+
+    fn visit_assign(&mut self,left:&Expr,right:&Expr)->Eval{
+        let value = right.accept(self)?;
+        if let Expr::ID{id} = left{
+            self.assign(&id.lexeme,value.clone());
+        }
+        Ok(value)
+    }
+
+    fn visit_arithmetic(&mut self,left:&Expr,operator:&Token,right:&Expr)->Eval{
+        let (left,right) = (self.int(left)?,self.int(right)?);
+        match operator.tokentype{
+            TokenType::PLUS => Ok(Value::Int(left.wrapping_add(right))),
+            _ => Ok(Value::Int(left.wrapping_sub(right)))
+        }
+    }
+
+    fn visit_factor(&mut self,left:&Expr,operator:&Token,right:&Expr)->Eval{
+        let (left,right) = (self.int(left)?,self.int(right)?);
+        match operator.tokentype{
+            TokenType::STAR => Ok(Value::Int(left.wrapping_mul(right))),
+            _ if right == 0 => Err(self.error(operator.line,"Division by zero.".to_string())),
+            _ => Ok(Value::Int(left.wrapping_div(right)))
+        }
+    }
+
+    //= compares Int , Bool and String by value and every other object by identity
+    fn visit_comparison(&mut self,left:&Expr,operator:&Token,right:&Expr)->Eval{
+        if operator.tokentype == TokenType::EQUALITY{
+            let (left,right) = (left.accept(self)?,right.accept(self)?);
+            return Ok(Value::Bool(left == right));
+        }
+        let (left,right) = (self.int(left)?,self.int(right)?);
+        let result = match operator.tokentype{
+            TokenType::LESSTHAN => left < right,
+            TokenType::LESSEQUAL => left <= right,
+            TokenType::MORETHAN => left > right,
+            _ => left >= right
+        };
+        Ok(Value::Bool(result))
+    }
+
+    fn visit_bitwise_not(&mut self,bitwise_not_expr:&Expr)->Eval{
+        Ok(Value::Int(self.int(bitwise_not_expr)?.wrapping_neg()))
+    }
+
+    fn visit_not(&mut self,not_expr:&Expr)->Eval{
+        Ok(Value::Bool(!self.bool(not_expr)?))
+    }
+
+    fn visit_new(&mut self,new_expr:&Token)->Eval{
+        let class = if new_expr.lexeme == SELF_TYPE{
+            let self_value = self.frame().self_value.clone();
+            self.class_of(&self_value)
+        }
+        else{
+            new_expr.lexeme.clone()
+        };
+        self.instantiate(&class,new_expr.line)
+    }
+
+    //the heap only grows , objects are never reclaimed . delete only evaluates its operand
+    fn visit_delete(&mut self,delete_expr:&Expr)->Eval{
+        delete_expr.accept(self)?;
+        Ok(Value::Void)
+    }
+
+    fn visit_isvoid(&mut self,isvoid_expr:&Expr)->Eval{
+        Ok(Value::Bool(isvoid_expr.accept(self)? == Value::Void))
+    }
+
+    fn visit_grouping(&mut self,grouping_expr:&Expr)->Eval{
+        grouping_expr.accept(self)
+    }
+
+    fn visit_block(&mut self,exprs:&Vec<Expr>)->Eval{
+        let mut value = Value::Void;
+        for expr in exprs{
+            value = expr.accept(self)?;
+        }
+        Ok(value)
+    }
+
+    fn visit_if(&mut self,condition:&Expr,body:&Expr,else_expr:&Expr)->Eval{
+        if self.bool(condition)? {body.accept(self)} else {else_expr.accept(self)}
+    }
+
+    fn visit_while(&mut self,condition:&Expr,body:&Expr)->Eval{
+        while self.bool(condition)?{
+            body.accept(self)?;
+        }
+        Ok(Value::Void)
+    }
+
+    //the branch with the closest ancestor of the dynamic type wins
+    fn visit_case(&mut self,condition:&Expr,branches:&Vec<Expr>)->Eval{
+        let value = condition.accept(self)?;
+        if value == Value::Void{
+            return Err(self.error(condition.line(),"Match on void in case statement.".to_string()));
+        }
+        let class = self.class_of(&value);
+        for ancestor in self.table.ancestors(&class){
+            let branch = branches.iter().find(|b| matches!(b,Expr::Branch{type_,..} if type_.lexeme == ancestor));
+            if let Some(Expr::Branch{id,expr,..}) = branch{
+                self.bind(&id.lexeme,value);
+                let result = expr.accept(self);
+                self.frame().scopes.pop();
+                return result;
+            }
+        }
+        Err(self.error(condition.line(),format!("No match in case statement for Class {}",class)))
+    }
+
+    fn visit_branch(&mut self,_id:&Token,_type_:&Token,expr:&Expr)->Eval{
+        expr.accept(self)
+    }
+
+    fn visit_let(&mut self,declarations:&Vec<Expr>,body:&Expr)->Eval{
+        let depth = self.frame().scopes.len();
+        let mut result = Ok(Value::Void);
+        for declaration in declarations{
+            result = declaration.accept(self);
+            if result.is_err(){
+                break;
+            }
+        }
+        if result.is_ok(){
+            result = body.accept(self);
+        }
+        self.frame().scopes.truncate(depth);
+        result
+    }
+
+    //binds the variable , visit_let drops the scope after the body
+    fn visit_declaration(&mut self,id:&Token,type_:&Token,expr:&Option<Expr>)->Eval{
+        let value = match expr{
+            Some(expr) => expr.accept(self)?,
+            None => Self::default_value(&type_.lexeme)
+        };
+        self.bind(&id.lexeme,value.clone());
+        Ok(value)
+    }
+
+    fn visit_stringliteral(&mut self,stringliteral:&Token)->Eval{
+        Ok(Value::Str(stringliteral.string_value().to_string()))
+    }
+
+    fn visit_integerliteral(&mut self,integerliteral:&Token)->Eval{
+        Ok(Value::Int(integerliteral.int_value()))
+    }
+
+    fn visit_boolliteral(&mut self,boolliteral:&Token)->Eval{
+        Ok(Value::Bool(boolliteral.bool_value()))
+    }
+
+    fn visit_id(&mut self,id:&Token)->Eval{
+        Ok(self.lookup(&id.lexeme))
+    }
+
+    fn visit_dispatch(&mut self,_id:NodeId,target:&Option<Token>,expr:&Expr,method_name:&Option<Token>,arguments:&Vec<Expr>)->Eval{
+        let mut values = vec![];
+        for argument in arguments{
+            values.push(argument.accept(self)?);
+        }
+        let (receiver,method) = match (method_name,expr){
+            (Some(method),_) => (expr.accept(self)?,method),
+            (None,Expr::ID{id}) => (self.frame().self_value.clone(),id),
+            (None,_) => return Ok(Value::Void)
+        };
+        if receiver == Value::Void{
+            return Err(self.error(method.line,"Dispatch to void.".to_string()));
+        }
+        let class = match target{
+            Some(target) => target.lexeme.clone(),
+            None => self.class_of(&receiver)
+        };
+        self.call(receiver,&class,method,values)
+    }
+
+    fn not_implemented(&mut self)->Eval{
+        Ok(Value::Void)
+    }
+}
@@ -0,0 +1,35 @@
+#![allow(non_snake_case,non_camel_case_types,clippy::upper_case_acronyms,clippy::ptr_arg)]
+
+/*
+cool compiler front end , checker and back ends as a library
+
+Scanner turns source text into tokens , Parser turns tokens into an ast::Program ,
+semant::check type checks a program and asm::generate / interpreter::run execute it.
+errors from the scanner and the parser come back as diagnostic::Diagnostics , render shows them.
+Parser::parse_cst builds the lossless cst::SyntaxNode tree instead , lower::lower turns it into the same ast.
+formatter::format prints source back in the canonical layout , astdump prints the ast like the reference parser.
+astreader reads such a dump back into an ast::Program , astexport writes the ast as json or an s-expression.
+*/
+
+pub mod token;
+pub mod diagnostic;
+pub mod render;
+pub mod scanner;
+pub mod parser;
+pub mod cst;
+pub mod lower;
+pub mod formatter;
+pub mod ast;
+pub mod astprinter;
+pub mod astdump;
+pub mod astreader;
+pub mod astexport;
+pub mod tokenprinter;
+pub mod semant;
+pub mod asm;
+pub mod interpreter;
+
+pub use scanner::Scanner;
+pub use parser::Parser;
+pub use token::{Token,TokenType,Span,Literal,Trivia,TriviaKind};
+pub use diagnostic::{Diagnostic,Diagnostics,Severity};
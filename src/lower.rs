@@ -0,0 +1,195 @@
+use crate::ast::*;
+use crate::cst::*;
+use crate::token::*;
+
+/*
+lowers a syntax tree to the ast
+
+the tree has to come from a program that parsed without errors , lower gives None when it runs into
+an ERROR node or a node that is missing a piece . the ast is the one parse_program builds from the same
+tokens , spans included , so every later phase works the same on either.
+*/
+
+pub fn lower(root:&SyntaxNode)->Option<Program>{
+    let lowering = Lowering::new(&root.text());
+    let mut classes = vec![];
+    for node in root.children(){
+        match node.kind(){
+            SyntaxKind::CLASS => classes.push(lowering.class(&node)?),
+            _ => return None
+        }
+    }
+    Some(Program::new(classes))
+}
+
+struct Lowering{
+    text : String,
+    lines : Vec<usize>//offset where every line starts
+}
+
+impl Lowering{
+    fn new(text:&str)->Lowering{
+        let mut lines = vec![0];
+        lines.extend(text.match_indices('\n').map(|(i,_)| i + 1));
+        Lowering{text:text.to_string(),lines}
+    }
+
+    //line and column (in characters) of a byte offset , both starting at 1
+    fn position(&self,offset:usize)->(usize,usize){
+        let line = self.lines.partition_point(|start| *start <= offset);
+        let start = self.lines[line - 1];
+        (line,self.text[start..offset].chars().count() + 1)
+    }
+
+    fn span(&self,start:usize,end:usize)->Span{
+        let (line,column) = self.position(start);
+        Span::new(start,end,line,column)
+    }
+
+    fn node_span(&self,node:&SyntaxNode)->Span{
+        let range = node.trimmed_range();
+        self.span(range.start,range.end)
+    }
+
+    fn token(&self,token:&SyntaxToken)->Token{
+        let range = token.text_range();
+        let (end_line,_) = self.position(range.end);
+        Token::new(end_line,self.span(range.start,range.end),token.text().to_string(),token.kind(),token.literal().cloned())
+    }
+
+    //the n-th token of the given kind directly under node
+    fn find(&self,node:&SyntaxNode,kind:TokenType,n:usize)->Option<Token>{
+        node.tokens().iter().filter(|t| t.kind() == kind).nth(n).map(|t| self.token(t))
+    }
+
+    fn has(&self,node:&SyntaxNode,kind:TokenType)->bool{
+        node.tokens().iter().any(|t| t.kind() == kind)
+    }
+
+    fn class(&self,node:&SyntaxNode)->Option<Class>{
+        let name = self.find(node,TokenType::TYPEID,0)?;
+        let inherits = self.find(node,TokenType::TYPEID,1);
+        let mut features = vec![];
+        for child in node.children(){
+            features.push(match child.kind(){
+                SyntaxKind::METHOD => self.method(&child)?,
+                SyntaxKind::ATTRIBUTE => self.attribute(&child)?,
+                _ => return None
+            });
+        }
+        Some(Class::new(name,inherits,features,self.node_span(node)))
+    }
+
+    fn method(&self,node:&SyntaxNode)->Option<Feature>{
+        let id = self.find(node,TokenType::OBJECTID,0)?;
+        let type_ = self.find(node,TokenType::TYPEID,0)?;
+        let mut formals = vec![];
+        let mut body = None;
+        for child in node.children(){
+            match child.kind(){
+                SyntaxKind::FORMAL => formals.push(self.formal(&child)?),
+                _ if body.is_none() => body = Some(self.expr(&child)?),
+                _ => return None
+            }
+        }
+        Some(Feature::new_method(id,type_,formals,body?,self.node_span(node)))
+    }
+
+    fn attribute(&self,node:&SyntaxNode)->Option<Feature>{
+        let id = self.find(node,TokenType::OBJECTID,0)?;
+        let type_ = self.find(node,TokenType::TYPEID,0)?;
+        let init = match node.children().first(){
+            Some(child) => Some(self.expr(child)?),
+            None => None
+        };
+        Some(Feature::new_attribute(id,type_,init))
+    }
+
+    fn formal(&self,node:&SyntaxNode)->Option<Formal>{
+        Some(Formal::new(self.find(node,TokenType::OBJECTID,0)?,self.find(node,TokenType::TYPEID,0)?))
+    }
+
+    fn exprs(&self,nodes:&[SyntaxNode])->Option<Vec<Expr>>{
+        nodes.iter().map(|n| self.expr(n)).collect()
+    }
+
+    //the only child expression of node
+    fn operand(&self,node:&SyntaxNode)->Option<Expr>{
+        match node.children().as_slice(){
+            [child] => self.expr(child),
+            _ => None
+        }
+    }
+
+    fn expr(&self,node:&SyntaxNode)->Option<Expr>{
+        let children = node.children();
+        let span = self.node_span(node);
+        Some(match node.kind(){
+            SyntaxKind::ASSIGN_EXPR =>{
+                let [left,right] = children.as_slice() else {return None};
+                Expr::Assign(self.expr(left)?,self.expr(right)?)
+            },
+            SyntaxKind::BINARY_EXPR =>{
+                let [left,right] = children.as_slice() else {return None};
+                let operator = node.tokens().first().map(|t| self.token(t))?;
+                let (left,right) = (self.expr(left)?,self.expr(right)?);
+                match operator.tokentype{
+                    TokenType::PLUS|TokenType::MINUS => Expr::Arithmetic(left,operator,right),
+                    TokenType::STAR|TokenType::SLASH => Expr::Factor(left,operator,right),
+                    _ => Expr::Comparison(left,operator,right)
+                }
+            },
+            SyntaxKind::NOT_EXPR => Expr::Not(self.operand(node)?,span),
+            SyntaxKind::NEG_EXPR => Expr::BitWiseNot(self.operand(node)?,span),
+            SyntaxKind::ISVOID_EXPR => Expr::IsVoid(self.operand(node)?,span),
+            SyntaxKind::DELETE_EXPR => Expr::Delete(self.operand(node)?,span),
+            SyntaxKind::PAREN_EXPR => Expr::Grouping(self.operand(node)?,span),
+            SyntaxKind::NEW_EXPR => Expr::New(self.find(node,TokenType::TYPEID,0)?,span),
+            SyntaxKind::DISPATCH_EXPR =>{
+                let [receiver,arguments] = children.as_slice() else {return None};
+                let target = if self.has(node,TokenType::AT) {Some(self.find(node,TokenType::TYPEID,0)?)} else {None};
+                let method = if self.has(node,TokenType::DOT) {Some(self.find(node,TokenType::OBJECTID,0)?)} else {None};
+                Expr::Dispatch(target,self.expr(receiver)?,method,self.exprs(&arguments.children())?,span)
+            },
+            SyntaxKind::IF_EXPR =>{
+                let [condition,then,else_] = children.as_slice() else {return None};
+                Expr::IF_EXPR(self.expr(condition)?,self.expr(then)?,self.expr(else_)?,span)
+            },
+            SyntaxKind::WHILE_EXPR =>{
+                let [condition,body] = children.as_slice() else {return None};
+                Expr::WHILE_EXPR(self.expr(condition)?,self.expr(body)?,span)
+            },
+            SyntaxKind::BLOCK_EXPR => Expr::BLOCK_EXPR(self.exprs(&children)?,span),
+            SyntaxKind::LET_EXPR =>{
+                let (body,bindings) = children.split_last()?;
+                Expr::LET_EXPR(self.exprs(bindings)?,self.expr(body)?,span)
+            },
+            SyntaxKind::LET_BINDING =>{
+                let init = match children.first(){
+                    Some(child) => Some(self.expr(child)?),
+                    None => None
+                };
+                Expr::Declaration(self.find(node,TokenType::OBJECTID,0)?,self.find(node,TokenType::TYPEID,0)?,init)
+            },
+            SyntaxKind::CASE_EXPR =>{
+                let (expr,branches) = children.split_first()?;
+                Expr::CASE_EXPR(self.expr(expr)?,self.exprs(branches)?,span)
+            },
+            SyntaxKind::CASE_BRANCH => Expr::Branch{
+                id:self.find(node,TokenType::OBJECTID,0)?,
+                type_:self.find(node,TokenType::TYPEID,0)?,
+                expr:Box::new(self.operand(node)?)
+            },
+            SyntaxKind::NAME_EXPR => Expr::ID(self.find(node,TokenType::OBJECTID,0)?),
+            SyntaxKind::LITERAL =>{
+                let token = node.tokens().first().map(|t| self.token(t))?;
+                match token.tokentype{
+                    TokenType::INTEGER => Expr::IntegerLiteral(token),
+                    TokenType::STRING => Expr::StringLiteral(token),
+                    _ => Expr::BoolLiteral(token)
+                }
+            },
+            _ => return None
+        })
+    }
+}
@@ -1,60 +1,391 @@
-use scanner::Scanner;
-use parser::Parser;
+#![allow(non_snake_case,clippy::upper_case_acronyms)]
+
+use cool_compiler_rs::{Scanner,Parser};
+use cool_compiler_rs::{ast,astprinter,astdump,astreader,astexport,tokenprinter,semant,asm,interpreter,render,formatter};
 
 use std::io;
-mod token;
-mod scanner;
-mod parser;
-mod ast;
-mod astprinter;
+
+/*
+command line driver , modeled on the flags of the reference coolc
+
+all input files are compiled together as one program . the stage flags stop after that stage ,
+without one the program is compiled to mips assembly . debug dumps are only printed when asked for.
+a file ending in .ast is not source but the ast dump of the reference parser (coolc -p) , it is read
+instead of scanned and parsed , so the later stages can be run on the tree of the reference parser.
+
+exit codes follow sysexits.h
+64 bad command line , 65 errors in the program , 70 runtime error under --run , 74 file errors
+fmt --check exits with 1 when a file is not formatted , like rustfmt --check
+*/
+
+const USAGE:&str = "Usage: cool-compiler-rs [options] file.cl [file.cl ...]
+       cool-compiler-rs fmt [--check] [--indent <n>] [--width <n>] file.cl [file.cl ...]
+       cool-compiler-rs                start the interactive prompt
+
+Options:
+  --lex          stop after scanning and print the tokens like the reference lexer
+  --parse        stop after parsing and print the ast
+  --emit=ast     stop after parsing and print the ast like the reference parser (coolc -p)
+  --emit=ast-json
+  --emit=ast-sexp
+                 stop after parsing and print the ast as json or as an s-expression , see astexport.rs
+  --semant       stop after semantic analysis
+  --codegen      compile to mips assembly (the default)
+  --run          interpret the program instead of compiling it
+  -o <file>      write the assembly to <file> instead of <first input>.s
+  -g             use the generational garbage collector
+  --dump-tokens  also print the tokens of every file
+  --dump-ast     also print the ast of the program
+  -h, --help     print this message
+
+a file.ast input is read as the ast printed by the reference parser (coolc -p) instead of being parsed
+
+fmt rewrites the files in the canonical layout:
+  --check        only list the files that are not formatted , exit with 1 if there are any
+  --indent <n>   indent by <n> spaces (default 4)
+  --width <n>    break lines longer than <n> characters (default 80)";
+
+#[derive(Debug,Copy,Clone,PartialEq)]
+enum Stage{
+    LEX,
+    PARSE,
+    SEMANT,
+    CODEGEN,
+    RUN
+}
+
+//formats of the ast printed by --emit
+#[derive(Debug,Copy,Clone,PartialEq)]
+enum Emit{
+    AST,
+    JSON,
+    SEXP
+}
+
+struct FmtOptions{
+    check : bool,
+    format : formatter::FormatOptions,
+    files : Vec<String>
+}
+
+struct Options{
+    stage : Stage,
+    output : Option<String>,
+    gc : bool,
+    dump_tokens : bool,
+    dump_ast : bool,
+    emit : Option<Emit>,
+    files : Vec<String>
+}
 
 fn main() {
-    let args = std::env::args().collect::<Vec<String>>();
-    if(args.len() > 2){
-        println!("Usage: jcc [script]");
-        std::process::exit(64);
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if args.is_empty(){
+        runprompt();
+        return;
     }
-    else if args.len() == 2{
-        runFile();
+    if args[0] == "fmt"{
+        match parseFmtArgs(&args[1..]){
+            Ok(options) => fmt(&options),
+            Err(message) => usage(&message)
+        }
+        return;
     }
-    else{
-        runprompt();
+    let options = match parseArgs(&args){
+        Ok(options) => options,
+        Err(message) => usage(&message)
+    };
+    compile(&options);
+}
+
+fn usage(message:&str)->!{
+    if !message.is_empty(){
+        eprintln!("{}",message);
     }
+    eprintln!("{}",USAGE);
+    std::process::exit(64);
 }
 
+fn parseArgs(args:&[String])->Result<Options,String>{
+    let mut options = Options{
+        stage:Stage::CODEGEN,
+        output:None,
+        gc:false,
+        dump_tokens:false,
+        dump_ast:false,
+        emit:None,
+        files:vec![]
+    };
+    let mut stage = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next(){
+        let next_stage = match arg.as_str(){
+            "--lex" => Some(Stage::LEX),
+            "--parse" => Some(Stage::PARSE),
+            "--semant" => Some(Stage::SEMANT),
+            "--codegen" => Some(Stage::CODEGEN),
+            "--run" => Some(Stage::RUN),
+            _ if arg.starts_with("--emit=") => Some(Stage::PARSE),
+            _ => None
+        };
+        if let Some(format) = arg.strip_prefix("--emit="){
+            options.emit = Some(match format{
+                "ast" => Emit::AST,
+                "ast-json" => Emit::JSON,
+                "ast-sexp" => Emit::SEXP,
+                _ => return Err(format!("unknown --emit format {}",format))
+            });
+        }
+        if let Some(next_stage) = next_stage{
+            if stage.is_some_and(|s| s != next_stage){
+                return Err("only one of --lex , --parse , --emit , --semant , --codegen and --run can be given".to_string());
+            }
+            stage = Some(next_stage);
+            continue;
+        }
+        match arg.as_str(){
+            "-o" => match args.next(){
+                Some(output) => options.output = Some(output.clone()),
+                None => return Err("-o needs a file name".to_string())
+            },
+            "-g" => options.gc = true,
+            "--dump-tokens" => options.dump_tokens = true,
+            "--dump-ast" => options.dump_ast = true,
+            "-h"|"--help" =>{
+                println!("{}",USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}",arg)),
+            _ => options.files.push(arg.clone())
+        }
+    }
+    options.stage = stage.unwrap_or(Stage::CODEGEN);
+    if options.files.is_empty(){
+        return Err("no input files".to_string());
+    }
+    if (options.stage == Stage::LEX || options.dump_tokens) && options.files.iter().any(|f| isAstDump(f)){
+        return Err("--lex and --dump-tokens need source files , not ast dumps".to_string());
+    }
+    if options.output.is_some() && options.stage != Stage::CODEGEN{
+        return Err("-o only applies when generating code".to_string());
+    }
+    Ok(options)
+}
 
-fn runFile(){
-    let filename = std::env::args().nth(1).unwrap();
-    let source = match std::fs::read_to_string(&filename){
-        Ok(source) => source,
-        Err(e) => {
-            println!("Error reading file: {}",e);
+fn parseFmtArgs(args:&[String])->Result<FmtOptions,String>{
+    let mut options = FmtOptions{check:false,format:formatter::FormatOptions::default(),files:vec![]};
+    let mut args = args.iter();
+    while let Some(arg) = args.next(){
+        match arg.as_str(){
+            "--check" => options.check = true,
+            "--indent"|"--width" =>{
+                let value = match args.next().map(|n| n.parse::<usize>()){
+                    Some(Ok(value)) if value > 0 => value,
+                    _ => return Err(format!("{} needs a positive number",arg))
+                };
+                if arg == "--indent"{
+                    options.format.indent = value;
+                }
+                else{
+                    options.format.width = value;
+                }
+            },
+            "-h"|"--help" =>{
+                println!("{}",USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}",arg)),
+            _ => options.files.push(arg.clone())
+        }
+    }
+    if options.files.is_empty(){
+        return Err("no input files".to_string());
+    }
+    Ok(options)
+}
+
+//formats every file in place , or with --check only reports the ones that would change
+fn fmt(options:&FmtOptions){
+    let mut failed = false;
+    let mut unformatted = false;
+    for filename in options.files.iter(){
+        let source = match std::fs::read_to_string(filename){
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error reading file {}: {}",filename,e);
+                std::process::exit(74);
+            }
+        };
+        let formatted = match formatter::format(&source,&options.format){
+            Ok(formatted) => formatted,
+            Err(diagnostics) => {
+                let renderer = render::Renderer::new(filename,&source,useColor());
+                for diagnostic in diagnostics.iter(){
+                    eprint!("{}",renderer.render(diagnostic));
+                }
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == source{
+            continue;
+        }
+        if options.check{
+            println!("{} is not formatted",filename);
+            unformatted = true;
+        }
+        else if let Err(e) = std::fs::write(filename,formatted){
+            eprintln!("Error writing file {}: {}",filename,e);
             std::process::exit(74);
         }
-    };
-    let mut scanner = Scanner::new(source);
-    let tokens = match scanner.scan_tokens(){
-        Ok(tokens) => tokens,
-        Err(e) => {
-            eprintln!("{}",e);
+    }
+    if failed{
+        eprintln!("Formatting halted due to lex and parse errors");
+        std::process::exit(65);
+    }
+    if unformatted{
+        std::process::exit(1);
+    }
+}
+
+//reads , scans and parses every file . the returned program holds the classes of all of them
+fn parseFiles(options:&Options)->ast::Program{
+    let mut program = ast::Program::new(vec![]);
+    let mut failed = false;
+    for filename in options.files.iter(){
+        let source = match std::fs::read_to_string(filename){
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error reading file {}: {}",filename,e);
+                std::process::exit(74);
+            }
+        };
+        let renderer = render::Renderer::new(filename,&source,useColor());
+        if isAstDump(filename){
+            match astreader::AstReader::read_program(&source){
+                Ok(file) => program.extend(file),
+                Err(diagnostic) => {
+                    eprint!("{}",renderer.render(&diagnostic));
+                    failed = true;
+                }
+            }
+            continue;
+        }
+        let mut scanner = Scanner::new(source.clone());
+        let (tokens,diagnostics) = scanner.scan_all();
+        if options.stage == Stage::LEX || options.dump_tokens{
+            print!("{}",tokenprinter::TokenPrinter::print_tokens(filename,&tokens));
+        }
+        if diagnostics.has_errors(){
+            for diagnostic in diagnostics.iter(){
+                eprint!("{}",renderer.render(diagnostic));
+            }
+            failed = true;
+            continue;
+        }
+        if options.stage == Stage::LEX{
+            continue;
+        }
+        let mut p = Parser::new(tokens);
+        match p.parse_program(){
+            Ok(mut file) => {
+                file.set_filename(filename);
+                program.extend(file);
+            }
+            Err(diagnostics) => {
+                for diagnostic in diagnostics.iter(){
+                    eprint!("{}",renderer.render(diagnostic));
+                }
+                failed = true;
+            }
+        };
+    }
+    if failed{
+        eprintln!("Compilation halted due to lex and parse errors");
+        std::process::exit(65);
+    }
+    program
+}
+
+//the classes of a dump keep the file names recorded in it
+fn isAstDump(filename:&str)->bool{
+    std::path::Path::new(filename).extension().is_some_and(|e| e == "ast")
+}
+
+fn compile(options:&Options){
+    let prog = parseFiles(options);
+    if options.stage == Stage::LEX{
+        return;
+    }
+    match options.emit{
+        Some(Emit::AST) => print!("{}",astdump::AstDump::dump_program(&prog)),
+        Some(Emit::JSON) => print!("{}",astexport::json(&prog)),
+        Some(Emit::SEXP) => print!("{}",astexport::sexp(&prog)),
+        None if options.stage == Stage::PARSE || options.dump_ast => println!("{}",astprinter::AstPrinter::print_program(&prog)),
+        None => ()
+    }
+    if options.stage == Stage::PARSE{
+        return;
+    }
+
+    let analysis = match semant::check(&prog){
+        Ok(analysis) => analysis,
+        Err(errors) => {
+            for error in errors.iter(){
+                eprintln!("{}",error.report());
+            }
+            eprintln!("Compilation halted due to static semantic errors.");
             std::process::exit(65);
         }
     };
 
-    for tok in tokens.iter(){
-        println!("{:?}",tok);
+    match options.stage{
+        Stage::SEMANT => (),
+        Stage::RUN => runProgram(&prog,&analysis),
+        _ => {
+            let output = match &options.output{
+                Some(output) => std::path::PathBuf::from(output),
+                None => std::path::Path::new(&options.files[0]).with_extension("s")
+            };
+            if let Err(e) = std::fs::write(&output,asm::generate(&prog,&analysis,options.gc)){
+                eprintln!("Error writing file {}: {}",output.display(),e);
+                std::process::exit(74);
+            }
+        }
     }
+}
 
-    let mut  p =  Parser::new(tokens);
+//color only when a person is likely to read stderr , see https://no-color.org
+fn useColor()->bool{
+    use std::io::IsTerminal;
+    std::env::var_os("NO_COLOR").is_none() && io::stderr().is_terminal()
+}
 
-    let prog = match p.parse_program(){
-        Ok(prog) => prog,
-        Err(e) => {
-            eprintln!("{}",e);
-            std::process::exit(65);
+//deeply recursive cool programs need more stack than the main thread gets
+fn runProgram(prog:&ast::Program,analysis:&semant::Analysis){
+    let table = &analysis.table;
+    let result = std::thread::scope(|scope|{
+        std::thread::Builder::new()
+            .stack_size(1 << 30)
+            .spawn_scoped(scope,|| interpreter::run(prog,table))
+            .map(|thread| thread.join())
+    });
+    match result{
+        Ok(Ok(Ok(()))) => (),
+        Ok(Ok(Err(e))) =>{
+            eprintln!("{}",e.report());
+            std::process::exit(70);
         }
-    };
-    println!("{}",astprinter::AstPrinter::print_program(&prog));
+        //the panic message is already on stderr
+        Ok(Err(_)) =>{
+            eprintln!("Execution halted by an internal error of the interpreter.");
+            std::process::exit(70);
+        }
+        Err(e) =>{
+            eprintln!("Error starting the interpreter: {}",e);
+            std::process::exit(70);
+        }
+    }
 }
 
 
@@ -64,38 +395,37 @@ fn runprompt(){
     let mut source = String::new();
     println!("\nEnter the source code: ");
     match io::stdin().read_line(&mut source){
+        Ok(0) => return,
         Ok(_) => (),
         Err(e) => {
             println!("Error reading from stdin: {}",e);
             continue;
         }
     };
-    let mut scanner = Scanner::new(source);
+    let renderer = render::Renderer::new("<stdin>",&source,useColor());
+    let mut scanner = Scanner::new(source.clone());
     let tokens = match scanner.scan_tokens(){
         Ok(tokens) => tokens,
-        Err(e) => {
-            println!("{}",e);
+        Err(diagnostics) => {
+            for diagnostic in diagnostics.iter(){
+                eprint!("{}",renderer.render(diagnostic));
+            }
             continue;
         }
     };
 
-    for tok in tokens.iter(){
-        println!("{:?}",tok);
-    }
-
     let mut  p =  Parser::new(tokens);
 
     let prog = match p.parse_program(){
         Ok(prog) => prog,
-        Err(e) => {
-            println!("{}",e);
+        Err(diagnostics) => {
+            for diagnostic in diagnostics.iter(){
+                eprint!("{}",renderer.render(diagnostic));
+            }
             continue;
         }
     };
     println!("{}",astprinter::AstPrinter::print_program(&prog));
-    
+
 }
 }
-
-
-
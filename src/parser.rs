@@ -1,55 +1,146 @@
 use crate::ast::*;
+use crate::cst::*;
+use crate::diagnostic::*;
 use crate::token::*;
-pub struct Parser{
-    had_error : bool,
+/*
+the parser pulls tokens from any iterator as it goes , a Vec<Token> or the Scanner itself , and keeps
+one token of lookahead . tokens are never buffered beyond that , so the stream does not have to be
+materialized first.
+
+ERROR tokens were already reported by the scanner , the parser reads on as if they were not there .
+a stream that runs out without an EOF token is treated as if it ended with one.
+
+with parse_cst the parser also builds the lossless syntax tree (cst.rs) . every rule takes a checkpoint
+before its first token and wraps what it consumed into a node when it is done.
+*/
+pub struct Parser<'a>{
+    diagnostics : Diagnostics,
     panic_mode : bool,
-    tokens : Vec<Token>,
-    current : usize
-}
-impl Parser{
-
-pub fn new(tokens:Vec<Token>)->Parser{
+    tokens : Box<dyn Iterator<Item=Token> + 'a>,
+    previous : Token,
+    current : Token,
+    skipped : Vec<Token>,//ERROR tokens between previous and current
+    builder : Option<TreeBuilder>
+}
+impl<'a> Parser<'a>{
+
+pub fn new<I>(tokens:I)->Parser<'a> where I:IntoIterator<Item=Token>,I::IntoIter:'a{
+    let mut tokens : Box<dyn Iterator<Item=Token> + 'a> = Box::new(tokens.into_iter());
+    let mut skipped = vec![];
+    let current = Self::pull(&mut tokens,None,&mut skipped);
     Parser{
-        had_error:false,
+        diagnostics:Diagnostics::new(),
         panic_mode:false,
         tokens,
-        current:0
+        previous:current.clone(),
+        current,
+        skipped,
+        builder:None
     }
 }
 
-fn is_reserved(&self,type_:&str)->bool{
-    match type_{
-        "Object"|"SELF_TYPE"|"self"=> true,
-        _ => false
+//next token from the stream that is not an ERROR token , the ERROR tokens on the way are kept for the syntax tree
+fn pull(tokens:&mut Box<dyn Iterator<Item=Token> + 'a>,last:Option<&Token>,skipped:&mut Vec<Token>)->Token{
+    for token in tokens.by_ref(){
+        if token.tokentype != TokenType::ERROR{
+            return token;
+        }
+        skipped.push(token);
     }
+    Token::new(last.map_or(1,|t| t.line),Span::default(),"\0".to_string(),TokenType::EOF,None)
+}
+
+//adds the current token , and the lexical errors before it , to the syntax tree
+fn record(&mut self){
+    if let Some(builder) = self.builder.as_mut(){
+        for token in self.skipped.drain(..){
+            builder.token(&token);
+        }
+        builder.token(&self.current);
+    }
+}
+
+fn checkpoint(&self)->usize{
+    self.builder.as_ref().map_or(0,|b| b.checkpoint())
+}
+
+fn node(&mut self,checkpoint:usize,kind:SyntaxKind){
+    if let Some(builder) = self.builder.as_mut(){
+        builder.finish_at(checkpoint,kind);
+    }
+}
+
+//wraps an expression parsed since checkpoint into the node for its kind
+fn expr_node(&mut self,checkpoint:usize,expr:Expr)->Expr{
+    let kind = match &expr{
+        Expr::Assign{..} => SyntaxKind::ASSIGN_EXPR,
+        Expr::Comparison{..}|Expr::Arithmetic{..}|Expr::Factor{..} => SyntaxKind::BINARY_EXPR,
+        Expr::Not{..} => SyntaxKind::NOT_EXPR,
+        Expr::BitWiseNot{..} => SyntaxKind::NEG_EXPR,
+        Expr::IsVoid{..} => SyntaxKind::ISVOID_EXPR,
+        Expr::New{..} => SyntaxKind::NEW_EXPR,
+        Expr::Delete{..} => SyntaxKind::DELETE_EXPR,
+        Expr::Dispatch{..} => SyntaxKind::DISPATCH_EXPR,
+        Expr::If{..} => SyntaxKind::IF_EXPR,
+        Expr::While{..} => SyntaxKind::WHILE_EXPR,
+        Expr::Block{..} => SyntaxKind::BLOCK_EXPR,
+        Expr::Let{..} => SyntaxKind::LET_EXPR,
+        Expr::Declaration{..} => SyntaxKind::LET_BINDING,
+        Expr::Case{..} => SyntaxKind::CASE_EXPR,
+        Expr::Branch{..} => SyntaxKind::CASE_BRANCH,
+        Expr::Grouping{..} => SyntaxKind::PAREN_EXPR,
+        Expr::ID{..} => SyntaxKind::NAME_EXPR,
+        Expr::StringLiteral{..}|Expr::IntegerLiteral{..}|Expr::BoolLiteral{..} => SyntaxKind::LITERAL,
+        Expr::Error => SyntaxKind::ERROR
+    };
+    self.node(checkpoint,kind);
+    expr
+}
+
+fn is_reserved(&self,type_:&str)->bool{
+    matches!(type_,"Object"|"SELF_TYPE"|"self")
 }
 
-fn error(&mut self,message:&str,line:usize){
+fn error(&mut self,code:&'static str,message:&str,span:Span){
     if self.panic_mode{
         return;
     }
-    self.had_error = true;
-    eprintln!("Error at line {}: {}",line,message);
+    self.diagnostics.push(Diagnostic::error(code,message.to_string(),span));
+}
+
+//an error after which the parser no longer knows where it is . further errors are dropped until
+//a recovery point calls synchronize
+fn syntax_error(&mut self,code:&'static str,message:&str,span:Span){
+    self.error(code,message,span);
+    self.panic_mode = true;
+}
+
+fn had_error(&self)->bool{
+    self.diagnostics.has_errors()
 }
 
 fn eof(&self)->bool{
-    self.current >= self.tokens.len()-1
+    self.current.tokentype == TokenType::EOF
 }
-fn previous(&mut self)->&Token{
-    if self.current == 0 {
-        return &self.tokens[0];
-    }
-    &self.tokens[self.current - 1]
+fn previous(&self)->&Token{
+    &self.previous
 }
 fn peek(&self)->&Token{
-    &self.tokens[self.current]
+    &self.current
 }
 
 fn advance(&mut self)->&Token{
     if !self.eof(){
-        self.current += 1;
+        self.record();
+        let next = Self::pull(&mut self.tokens,Some(&self.current),&mut self.skipped);
+        self.previous = std::mem::replace(&mut self.current,next);
     }
-    &self.tokens[self.current - 1]
+    &self.previous
+}
+
+//from start up to and including the last consumed token
+fn span_from(&self,start:Span)->Span{
+    start.to(self.previous.span)
 }
 
 fn check(&self,token_type:TokenType)->bool{
@@ -57,7 +148,7 @@ fn check(&self,token_type:TokenType)->bool{
         false
     }
     else{
-        self.peek().clone().tokentype == token_type
+        self.peek().tokentype == token_type
     }
 }
 
@@ -77,62 +168,94 @@ fn consume(&mut self,token_type:TokenType,message:&str)->Token{
     }
     else{
        
-        self.error(message,self.peek().line);
-        self.panic_mode = true;
-        self.synchronize();
-        Token::new(0," ".to_string(),TokenType::ERROR,None)
+        self.syntax_error(EXPECTED_TOKEN,message,self.peek().span);
+        Token::new(0,Span::default()," ".to_string(),TokenType::ERROR,None)
     }
 }
 
+//leaves panic mode at the next place a feature or an expression in a block can start .
+//stops after a ; or before a } that are not nested in braces opened after the error , before class ,
+//and before a keyword starting an expression once at least one token has been skipped .
 pub fn synchronize(&mut self){
-    self.advance();
+    self.panic_mode = false;
+    let checkpoint = self.checkpoint();
+    let mut depth = 0usize;
+    let mut skipped = false;
     while !self.eof(){
         match self.peek().tokentype{
-            TokenType::SEMICOLON => {
+            TokenType::SEMICOLON if depth == 0 => {
                 self.advance();
-                return;
-            }
-            TokenType::KEYCLASS => {
-                return;
+                break;
             }
-            _ => {self.advance();}
+            TokenType::RIGHTBRACE if depth == 0 => break,
+            TokenType::KEYCLASS => break,
+            TokenType::KEYIF|TokenType::KEYWHILE|TokenType::KEYLET|TokenType::KEYCASE|TokenType::KEYNEW|
+            TokenType::KEYDELETE|TokenType::ISVOID|TokenType::NOT if depth == 0 && skipped => break,
+            TokenType::LEFTBRACE => depth += 1,
+            TokenType::RIGHTBRACE => depth -= 1,
+            _ => ()
         }
+        self.advance();
+        skipped = true;
+    }
+    if self.checkpoint() > checkpoint{
+        self.node(checkpoint,SyntaxKind::ERROR);
+    }
+}
+
+//a class that cannot be recovered is skipped as a whole
+fn synchronize_class(&mut self){
+    self.panic_mode = false;
+    let checkpoint = self.checkpoint();
+    while !self.eof() && !self.check(TokenType::KEYCLASS){
+        self.advance();
+    }
+    if self.checkpoint() > checkpoint{
+        self.node(checkpoint,SyntaxKind::ERROR);
     }
 }
 
-pub fn parse_program(&mut self)->Result<Program,&str>{
+pub fn parse_program(&mut self)->Result<Program,Diagnostics>{
         let mut classes:Vec<Class> = vec![];
         while !self.eof() {
             let class = self.parse_class();
             classes.push(class);
-            if self.had_error {break;}
-            self.consume(TokenType::SEMICOLON, "Expected ; after class definition");
+            if !self.panic_mode{
+                self.consume(TokenType::SEMICOLON, "Expected ; after class definition");
+            }
+            if self.panic_mode{
+                self.synchronize_class();
+            }
         }
-        if self.had_error {Err("Error in parsing")} else {Ok(Program::new(classes))}
+        //the EOF token holds the trivia at the end of the file
+        self.record();
+        if self.had_error() {Err(std::mem::take(&mut self.diagnostics))} else {Ok(Program::new(classes))}
+}
+
+//the syntax tree of the program , with the diagnostics . the tree is complete even when there are
+//errors , its text is always the text of the tokens it was given
+pub fn parse_cst(&mut self)->(SyntaxNode,Diagnostics){
+    self.builder = Some(TreeBuilder::new());
+    let diagnostics = match self.parse_program(){
+        Ok(_) => Diagnostics::new(),
+        Err(diagnostics) => diagnostics
+    };
+    let root = self.builder.take().unwrap_or_default().finish(SyntaxKind::PROGRAM);
+    (SyntaxNode::new_root(root),diagnostics)
 }
 
 fn parse_class(&mut self)->Class{
+    let checkpoint = self.checkpoint();
+    let start = self.peek().span;
     self.consume(TokenType::KEYCLASS, "Expected class definition");
-    let name = self.consume(TokenType::IDENTIFIER,"Expected class name");
-    if name.lexeme.chars().nth(0).unwrap().is_ascii_lowercase(){
-        self.error(format!("error at \"class {}\". Class name should start with an uppercase letter.",
-        name.lexeme.clone())
-        .as_str(),
-        name.line);
-    }
+    let name = self.consume(TokenType::TYPEID,"Expected class name");
     if self.is_reserved(&name.lexeme){
-        self.error(format!("error at \"class {}\". {} is a reserved keyword",&name.lexeme,&name.lexeme).as_str(),name.line);
+        self.error(RESERVED_NAME,format!("error at \"class {}\". {} is a reserved keyword",&name.lexeme,&name.lexeme).as_str(),name.span);
     }
     let inherits = match self.match_token(TokenType::KEYINHERITS){
-        true =>{ let name = self.consume(TokenType::IDENTIFIER,"Expected superclass name");
-        if name.lexeme.chars().nth(0).unwrap().is_ascii_lowercase(){
-            self.error(format!("error at \"inherits {}\". Class name should start with an uppercase letter.",
-            name.lexeme.clone())
-            .as_str(),
-            name.line);
-        }
+        true =>{ let name = self.consume(TokenType::TYPEID,"Expected superclass name");
         if self.is_reserved(&name.lexeme) && !name.lexeme.eq("Object"){
-            self.error(format!("error at \"inherits {}\". {} is a reserved keyword",&name.lexeme,&name.lexeme).as_str(),name.line);
+            self.error(RESERVED_NAME,format!("error at \"inherits {}\". {} is a reserved keyword",&name.lexeme,&name.lexeme).as_str(),name.span);
         }
         Some(name)
         },
@@ -140,25 +263,32 @@ fn parse_class(&mut self)->Class{
     };
     self.consume(TokenType::LEFTBRACE,"Expected { after class declaration");
     let mut features : Vec<Feature> = vec![];
-    while !self.check(TokenType::RIGHTBRACE) && !self.eof(){
+    while !self.check(TokenType::RIGHTBRACE) && !self.check(TokenType::KEYCLASS) && !self.eof(){
         features.push(self.parse_feature());
-        if self.had_error {break;}
-        self.consume(TokenType::SEMICOLON,"Expect ; after method or attribute definition");
+        if !self.panic_mode{
+            self.consume(TokenType::SEMICOLON,"Expect ; after method or attribute definition");
+        }
+        if self.panic_mode{
+            self.synchronize();
+        }
     }
     self.consume(TokenType::RIGHTBRACE, "Expect } after class definition");
-    Class::new(name, inherits, features)
+    self.node(checkpoint,SyntaxKind::CLASS);
+    Class::new(name, inherits, features,self.span_from(start))
 }
 
 fn parse_feature(&mut self)->Feature{
-    let id = self.consume(TokenType::IDENTIFIER, "Expect identifier");
-    if id.lexeme.chars().nth(0).unwrap().is_ascii_uppercase(){
-        self.error(format!("error at \"{}\". Attribute/Method name should start with a lowercase letter.",
-        id.lexeme.clone())
-        .as_str(),
-        id.line);
-    }
+    let checkpoint = self.checkpoint();
+    let feature = self.feature();
+    let kind = if feature.is_method() {SyntaxKind::METHOD} else {SyntaxKind::ATTRIBUTE};
+    self.node(checkpoint,kind);
+    feature
+}
+
+fn feature(&mut self)->Feature{
+    let id = self.consume(TokenType::OBJECTID, "Expect attribute or method name");
     if self.is_reserved(&id.lexeme){
-        self.error(format!("error at \"{}\". {} is a reserved keyword",&id.lexeme,&id.lexeme).as_str(),id.line);
+        self.error(RESERVED_NAME,format!("error at \"{}\". {} is a reserved keyword",&id.lexeme,&id.lexeme).as_str(),id.span);
     }
 
     if self.match_token(TokenType::LEFTPAREN){
@@ -168,15 +298,14 @@ fn parse_feature(&mut self)->Feature{
         self.parse_attribute(id)
     }
     else{
-        self.error("Illegal Feature syntax",self.peek().line);
-        self.synchronize();
+        self.syntax_error(ILLEGAL_FEATURE,"Illegal Feature syntax",self.peek().span);
         Feature::new_attribute(id, self.previous().clone(), None)
     }
     
 }
 
 fn parse_attribute(&mut self,id:Token)->Feature{
-    let type_ = self.consume(TokenType::IDENTIFIER, "Expect type name");
+    let type_ = self.consume(TokenType::TYPEID, "Expect type name");
     let expr = match self.match_token(TokenType::ASSIGN){
         true => Some(self.expression()),
         false => None
@@ -186,265 +315,278 @@ fn parse_attribute(&mut self,id:Token)->Feature{
 
 fn parse_method(&mut self,id:Token)->Feature{
     let mut formals : Vec<Formal> = vec![];
-    while !self.check(TokenType::RIGHTPAREN){
+    while !self.check(TokenType::RIGHTPAREN) && !self.panic_mode && !self.eof(){
         formals.push(self.parse_formal());
     }
     self.consume(TokenType::RIGHTPAREN, "Expect ) after method parameters");
     self.consume(TokenType::COLON,"Expect : before return type");
-    let type_ = self.consume(TokenType::IDENTIFIER, "Expect return type from method");
+    let type_ = self.consume(TokenType::TYPEID, "Expect return type from method");
     self.consume(TokenType::LEFTBRACE, "Expect { after method declaration");
     let expr = self.expression();
     self.consume(TokenType::RIGHTBRACE, "Expect } after method body");
-    Feature::new_method(id, type_, formals, expr)
+    let span = self.span_from(id.span);
+    Feature::new_method(id, type_, formals, expr,span)
 }
 
 
 fn parse_formal(&mut self)->Formal{
-    let id = self.consume(TokenType::IDENTIFIER, "expect identifier");
+    let checkpoint = self.checkpoint();
+    let id = self.consume(TokenType::OBJECTID, "Expect parameter name");
     self.consume(TokenType::COLON,"Expect colon after identifier");
-    let type_ = self.consume(TokenType::IDENTIFIER,"Expect type name");
+    let type_ = self.consume(TokenType::TYPEID,"Expect type name");
+    self.node(checkpoint,SyntaxKind::FORMAL);
     self.match_token(TokenType::COMMA);//eating up the comma if it exists
     Formal::new(id,type_)
 }
 
-pub fn parse_expression(&mut self )->Result<Expr,&str>{
+pub fn parse_expression(&mut self )->Result<Expr,Diagnostics>{
     let expr = self.expression();
-   match self.had_error{
-         true => Err("Error in parsing"),
+   match self.had_error(){
+         true => Err(std::mem::take(&mut self.diagnostics)),
          false => Ok(expr)
    }
 }
 fn expression(&mut self)->Expr{
-    self.assignment()
+    self.expression_bp(0)
 }
 
 fn if_expr(&mut self)->Expr{
+    let start = self.previous().span;
     let expr = self.expression();
     self.consume(TokenType::KEYTHEN,"Expected 'then' after if");
     let then_expr = self.expression();
     self.consume(TokenType::KEYELSE, "expect else after then");
     let else_expr = self.expression();
     self.consume(TokenType::KEYFI,"Expected 'fi' after else");
-    Expr::IF_EXPR(expr,then_expr,else_expr)
+    Expr::IF_EXPR(expr,then_expr,else_expr,self.span_from(start))
 }
 
 fn block(&mut self)->Expr{
+    let start = self.previous().span;
     let mut exprs = Vec::new();
-    while !self.eof() && !self.check(TokenType::RIGHTBRACE){
+    while !self.eof() && !self.check(TokenType::RIGHTBRACE) && !self.check(TokenType::KEYCLASS){
         exprs.push(self.expression());
-        self.consume(TokenType::SEMICOLON,"Use ; to seperate expressions in block");
+        if !self.panic_mode{
+            self.consume(TokenType::SEMICOLON,"Use ; to seperate expressions in block");
+        }
+        if self.panic_mode{
+            self.synchronize();
+        }
     }
-    if exprs.is_empty() {self.error("Empty block",self.peek().line);}
+    if exprs.is_empty() {self.error(EMPTY_BLOCK,"Empty block",self.peek().span);}
     self.consume(TokenType::RIGHTBRACE, "Expected '}' after block");
-    Expr::BLOCK_EXPR(exprs)
+    Expr::BLOCK_EXPR(exprs,self.span_from(start))
 }
 
 fn while_expr(&mut self)->Expr{
+    let start = self.previous().span;
     let condition = self.expression();
     self.consume(TokenType::KEYLOOP,"Expected 'loop' after condition");
     let body = self.expression();
     self.consume(TokenType::KEYPOOL,"Expected 'pool' after body");
-    Expr::WHILE_EXPR(condition,body)
+    Expr::WHILE_EXPR(condition,body,self.span_from(start))
 }
 
 fn let_expr(&mut self)->Expr{
+    let start = self.previous().span;
     let mut declarations : Vec<Expr> = vec![];
     while !self.check(TokenType::KEYIN){
-        let id = self.consume(TokenType::IDENTIFIER, "Expect identifier in Let expression");
+        let checkpoint = self.checkpoint();
+        let id = self.consume(TokenType::OBJECTID, "Expect identifier in Let expression");
         self.consume(TokenType::COLON,"Expect ':' after identifier in Let expression");
-        let type_ = self.consume(TokenType::IDENTIFIER,"Expect type after identifier in Let expression");
+        let type_ = self.consume(TokenType::TYPEID,"Expect type after identifier in Let expression");
         let expr = match self.match_token(TokenType::ASSIGN){
             true => Some(self.expression()),
             false => None
         };
-        declarations.push(Expr::Declaration(id,type_,expr));
+        let declaration = self.expr_node(checkpoint,Expr::Declaration(id,type_,expr));
+        declarations.push(declaration);
         if !self.match_token(TokenType::COMMA){
             break;
         }
     }
     self.consume(TokenType::KEYIN, "Expect 'in' after Let declarations");
     let body = self.expression();
-    Expr::LET_EXPR(declarations, body)
+    Expr::LET_EXPR(declarations, body,self.span_from(start))
 }
 
 fn case(&mut self)->Expr{
+    let start = self.previous().span;
     let expr = self.expression();
     self.consume(TokenType::KEYOF, "Expect 'of' after case expression");
     let mut branches = Vec::new();
-    while self.match_token(TokenType::IDENTIFIER) {
-        let id = self.previous().clone();
+    while self.check(TokenType::OBJECTID) {
+        let checkpoint = self.checkpoint();
+        let id = self.advance().clone();
         self.consume(TokenType::COLON,"Expected ':' after case branch");
-        let type_ = self.consume(TokenType::IDENTIFIER,"Expected type after case branch");
+        let type_ = self.consume(TokenType::TYPEID,"Expected type after case branch");
         self.consume(TokenType::RARROW,"Expected '=>' after case branch type");
         let body = self.expression();
         self.consume(TokenType::SEMICOLON, "expect ; after case branch body");
-        branches.push(Expr::Branch{id,type_,expr:Box::new(body)});
+        let branch = self.expr_node(checkpoint,Expr::Branch{id,type_,expr:Box::new(body)});
+        branches.push(branch);
     }
-    if branches.is_empty() {self.error("Empty case expression",self.peek().line);}
+    if branches.is_empty() {self.error(EMPTY_CASE,"Empty case expression",self.peek().span);}
     self.consume(TokenType::KEYESAC,"Expected 'esac' after case expression");
-    Expr::CASE_EXPR(expr,branches)
-}
-
-fn assignment(&mut self)->Expr{
-    let left = self.not();
-
-    if self.match_token(TokenType::ASSIGN){
-        match left{
-            Expr::ID{id:token} => {
-                let right = self.assignment();
-                Expr::Assign(Expr::ID(token),right)
-            }
-            _ => {
-                self.error("Invalid assignment target",self.peek().line);
-                Expr::Error  
-            }
-        
+    Expr::CASE_EXPR(expr,branches,self.span_from(start))
+}
+
+//precedence climbing . parses an operand , then keeps applying the infix operators that bind at least as
+//tightly as min_power . the right operand of an operator is parsed with a higher minimum for left
+//associative operators and the same one for right associative ones
+fn expression_bp(&mut self,min_power:u8)->Expr{
+    let checkpoint = self.checkpoint();
+    let mut expr = self.prefix();
+    //power of the operator that built expr in this loop , to catch a < b < c
+    let mut last = None;
+    while let Some((power,associativity)) = infix_power(self.peek().tokentype){
+        if power < min_power{
+            break;
         }
-    }
-    else{
-        left
-    }
-}
-
-
-fn not(&mut self)->Expr{
-    if self.match_token(TokenType::NOT){
-        let expr = self.not();
-        Expr::Not(expr)
-    }
-    else{
-        self.comparison()
-    }
-}
-
-
-fn comparison(&mut self)->Expr{
-    let mut expr = self.term();
-    while self.match_token(TokenType::LESSTHAN) || self.match_token(TokenType::LESSEQUAL) || self.match_token(TokenType::MORETHAN) || self.match_token(TokenType::MOREEQUAL)|| self.match_token(TokenType::EQUALITY){
-        let op = self.previous().clone();
-        let right = self.not();
-        expr = Expr::Comparison(expr,op,right);
-    }
-    expr
-}
-
-fn term(&mut self)->Expr{
-    let mut expr = self.factor();
-    while self.match_token(TokenType::PLUS) || self.match_token(TokenType::MINUS){
-        let op = self.tokens[self.current - 1].clone();
-        let right = self.factor();
-        expr = Expr::Arithmetic(expr,op,right);
-    }
-    expr
-}
-
-fn factor(&mut self)->Expr{
-    let mut expr = self.isvoid();
-    while self.match_token(TokenType::STAR) || self.match_token(TokenType::SLASH) {
-        let op = self.tokens[self.current - 1].clone();
-        let right = self.isvoid();
-        expr = Expr::Factor(expr,op,right);
+        let op = self.advance().clone();
+        if associativity == Associativity::NONE && last == Some(power){
+            let span = expr.span().to(op.span);
+            self.error(NON_ASSOCIATIVE,&format!("'{}' is non-associative , add parentheses",op.lexeme),span);
+        }
+        //a name before <- was already taken as an assignment by prefix
+        if op.tokentype == TokenType::ASSIGN{
+            let span = expr.span().to(op.span);
+            self.syntax_error(INVALID_ASSIGNMENT,"Invalid assignment target",span);
+            return self.expr_node(checkpoint,Expr::Error);
+        }
+        let right = self.expression_bp(match associativity{
+            Associativity::RIGHT => power,
+            _ => power + 1
+        });
+        expr = match op.tokentype{
+            TokenType::PLUS|TokenType::MINUS => Expr::Arithmetic(expr,op,right),
+            TokenType::STAR|TokenType::SLASH => Expr::Factor(expr,op,right),
+            _ => Expr::Comparison(expr,op,right)
+        };
+        expr = self.expr_node(checkpoint,expr);
+        last = Some(power);
     }
     expr
 }
 
-fn isvoid(&mut self)->Expr{
-    if self.match_token(TokenType::ISVOID){
-        let expr = self.isvoid();
-        Expr::IsVoid(expr)
-    }
-    else{
-        self.unary()
-    }
-}
-
-fn unary(&mut self)->Expr{
-    if self.match_token(TokenType::TILDA){
-        let expr = self.expression();
-        Expr::BitWiseNot(expr)
-    }
-   
-    else{
-        self.dispatch(None)
+//not , isvoid and ~ . the operand takes every infix operator that binds tighter than the prefix operator ,
+//so not a < b is not (a < b) and ~a + b is (~a) + b
+fn prefix(&mut self)->Expr{
+    let checkpoint = self.checkpoint();
+    let token_type = self.peek().tokentype;
+    match prefix_power(token_type){
+        Some(power) if !self.eof() =>{
+            let start = self.advance().span;
+            let operand = self.expression_bp(power);
+            let span = self.span_from(start);
+            let expr = match token_type{
+                TokenType::NOT => Expr::Not(operand,span),
+                TokenType::ISVOID => Expr::IsVoid(operand,span),
+                _ => Expr::BitWiseNot(operand,span)
+            };
+            self.expr_node(checkpoint,expr)
+        },
+        _ =>{
+            let expr = self.dispatch(checkpoint,None);
+            if !(matches!(expr,Expr::ID{..}) && self.match_token(TokenType::ASSIGN)){
+                return expr;
+            }
+            //a name followed by <- starts an assignment wherever it is , even as the operand of a tighter
+            //operator . like let the value extends as far right as it can , so 1 + x <- 2 * 3 is 1 + (x <- (2 * 3))
+            let value = self.expression_bp(ASSIGN_POWER);
+            self.expr_node(checkpoint,Expr::Assign(expr,value))
+        }
     }
 }
 
-fn dispatch(&mut self,expr:Option<Expr>)->Expr{
+//checkpoint is taken before the receiver
+fn dispatch(&mut self,checkpoint:usize,expr:Option<Expr>)->Expr{
     let mut  expr = match expr { Some(e) => e ,None =>self.primary()};
-    if(self.check(TokenType::LEFTPAREN)){
-        if self.previous().tokentype != TokenType::IDENTIFIER{
-            self.error("expression not a method name so it cannot be called", self.peek().line);
+    if self.check(TokenType::LEFTPAREN) {
+        if self.previous().tokentype != TokenType::OBJECTID{
+            self.error(INVALID_CALL,"expression not a method name so it cannot be called", expr.span());
         }
+        let arguments = self.checkpoint();
         self.consume(TokenType::LEFTPAREN, "");
-        expr = self.patch_dispatch(expr,None,None);
+        expr = self.patch_dispatch(checkpoint,arguments,expr,None,None);
     }
     let mut type_present = false;
     let mut type_ = match self.match_token(TokenType::AT){
-        true =>{ type_present=true;Some(self.consume(TokenType::IDENTIFIER, "Expect type name after @"))},
+        true =>{ type_present=true;Some(self.consume(TokenType::TYPEID, "Expect type name after @"))},
         false => None
     };
-    if (type_present && !self.check(TokenType::DOT)){
+    if type_present && !self.check(TokenType::DOT) {
         self.consume(TokenType::DOT,"Expect method call after @ expression");
     }
-    while(self.match_token(TokenType::DOT)){
-        if (!type_present){type_ = None;} 
-        let id = self.consume(TokenType::IDENTIFIER, "Expect method name for dispatch");
+    while self.match_token(TokenType::DOT) {
+        if !type_present {type_ = None;} 
+        let id = self.consume(TokenType::OBJECTID, "Expect method name for dispatch");
+        let arguments = self.checkpoint();
         self.consume(TokenType::LEFTPAREN,"cannot access attribute . add '()' after method name in case you want to call it");
-        expr = self.patch_dispatch(expr,type_.clone(),Some(id));
+        expr = self.patch_dispatch(checkpoint,arguments,expr,type_.clone(),Some(id));
         type_present = false;
     }
     if self.check(TokenType::AT){
-        return self.dispatch(Some(expr));
+        return self.dispatch(checkpoint,Some(expr));
     }
     if self.match_token(TokenType::LEFTPAREN){
-        let prev = self.previous().line;
-        self.error("Dispatch works on methods only . maybe you forgot adding  a '.'", prev);
+        let prev = self.previous().span;
+        self.error(INVALID_CALL,"Dispatch works on methods only . maybe you forgot adding  a '.'", prev);
         self.consume(TokenType::RIGHTPAREN,"");
     } 
     expr
 }
 
-fn patch_dispatch(&mut self,expr:Expr,type_:Option<Token>,id:Option<Token>)->Expr{
+//the ( is already consumed , list is the checkpoint before it
+fn patch_dispatch(&mut self,checkpoint:usize,list:usize,expr:Expr,type_:Option<Token>,id:Option<Token>)->Expr{
     let mut arguments : Vec<Expr> = vec![];
-    while(!self.check(TokenType::RIGHTPAREN)){
-        arguments.push(self.assignment());
+    while !self.check(TokenType::RIGHTPAREN) {
+        arguments.push(self.expression());
         if !self.match_token(TokenType::COMMA){break;}
     }
     self.consume(TokenType::RIGHTPAREN,"Expect ')' after call");
-    Expr::Dispatch(type_, expr, id, arguments)
+    self.node(list,SyntaxKind::ARG_LIST);
+    let span = self.span_from(expr.span());
+    self.expr_node(checkpoint,Expr::Dispatch(type_, expr, id, arguments,span))
 }
 
 fn primary(&mut self)->Expr{
-    if self.match_token(TokenType::IDENTIFIER){
-        Expr::ID(self.tokens[self.current - 1].clone())
+    let checkpoint = self.checkpoint();
+    let expr = self.primary_expr();
+    self.expr_node(checkpoint,expr)
+}
+
+fn primary_expr(&mut self)->Expr{
+    if self.match_token(TokenType::OBJECTID){
+        Expr::ID(self.previous.clone())
     }
     else if self.match_token(TokenType::INTEGER){
-        Expr::IntegerLiteral(self.tokens[self.current - 1].clone())
+        Expr::IntegerLiteral(self.previous.clone())
     }
     else if self.match_token(TokenType::STRING){
-        Expr::StringLiteral(self.tokens[self.current - 1].clone())
+        Expr::StringLiteral(self.previous.clone())
     }
-    else if self.match_token(TokenType::KEYTRUE){
-        Expr::BoolLiteral(self.tokens[self.current - 1].clone())
-    }
-    else if self.match_token(TokenType::KEYFALSE){
-        Expr::BoolLiteral(self.tokens[self.current - 1].clone())
+    else if self.match_token(TokenType::KEYTRUE) || self.match_token(TokenType::KEYFALSE){
+        Expr::BoolLiteral(self.previous.clone())
     }
     else if self.match_token(TokenType::KEYNEW){
-        let type_ = self.consume(TokenType::IDENTIFIER,"Expected type after new");
-        Expr::New(type_)
+        let start = self.previous().span;
+        let type_ = self.consume(TokenType::TYPEID,"Expected type after new");
+        Expr::New(type_,self.span_from(start))
     }
     else if self.match_token(TokenType::KEYDELETE){
+        let start = self.previous().span;
         let expr = self.expression();
-        Expr::Delete(expr)
+        Expr::Delete(expr,self.span_from(start))
     }
     else if self.match_token(TokenType::LEFTBRACE){
         self.block()
     }
     else if self.match_token(TokenType::LEFTPAREN){
+        let start = self.previous().span;
         let expr = self.expression();
         self.consume(TokenType::RIGHTPAREN,"Expected ')' after expression");
-        Expr::Grouping(expr)   
+        Expr::Grouping(expr,self.span_from(start))
     }
     else if self.match_token(TokenType::KEYIF) {
         self.if_expr()
@@ -459,9 +601,56 @@ fn primary(&mut self)->Expr{
         self.let_expr()
     }
     else{
-        self.error("Expected expression",self.peek().line);
+        self.syntax_error(EXPECTED_EXPRESSION,"Expected expression",self.peek().span);
         Expr::Error
     }
 }
 
-}
\ No newline at end of file
+}
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+enum Associativity{
+    LEFT,
+    RIGHT,
+    NONE
+}
+
+/*
+binding powers , the precedence table of the cool manual from the lowest up
+
+<-                  right associative
+not                 prefix
+<= < = > >=         non associative , a < b < c is an error
++ -                 left associative
+* /                 left associative
+isvoid              prefix
+~                   prefix
+@ .                 dispatch , parsed with the operand it applies to
+
+the even powers of the table leave room between levels , a prefix operator parses its operand with its
+own power so it takes everything from the levels above it.
+
+like in the grammar of the reference parser , let bodies and assignments extend as far right as possible
+wherever they start : 1 + let x : Int in x * 2 < y is 1 + (let x : Int in ((x * 2) < y)) , and a + x <- b
+is a + (x <- b) . the same goes for not , a * not b + c is a * (not (b + c))
+*/
+const ASSIGN_POWER:u8 = 2;
+
+fn infix_power(token_type:TokenType)->Option<(u8,Associativity)>{
+    match token_type{
+        TokenType::ASSIGN => Some((ASSIGN_POWER,Associativity::RIGHT)),
+        TokenType::LESSEQUAL|TokenType::LESSTHAN|TokenType::EQUALITY|TokenType::MOREEQUAL|TokenType::MORETHAN => Some((6,Associativity::NONE)),
+        TokenType::PLUS|TokenType::MINUS => Some((8,Associativity::LEFT)),
+        TokenType::STAR|TokenType::SLASH => Some((10,Associativity::LEFT)),
+        _ => None
+    }
+}
+
+fn prefix_power(token_type:TokenType)->Option<u8>{
+    match token_type{
+        TokenType::NOT => Some(4),
+        TokenType::ISVOID => Some(12),
+        TokenType::TILDA => Some(14),
+        _ => None
+    }
+}
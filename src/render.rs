@@ -0,0 +1,91 @@
+use crate::diagnostic::*;
+
+/*
+renders diagnostics the way rustc does
+
+error[E0100]: expect else after then
+ --> examples/foo.cl:2:30
+  |
+2 |   main() : Int { if 1 then 2 fi };
+  |                              ^^
+  = note: ...
+
+the underline comes from the span of the diagnostic , a span running over several lines is
+underlined up to the end of its first line.
+*/
+
+const RESET:&str = "\x1b[0m";
+const BOLD:&str = "\x1b[1m";
+const RED:&str = "\x1b[1;31m";
+const YELLOW:&str = "\x1b[1;33m";
+const GREEN:&str = "\x1b[1;32m";
+const BLUE:&str = "\x1b[1;34m";
+
+pub struct Renderer<'a>{
+    filename : &'a str,
+    source : &'a str,
+    color : bool
+}
+
+impl<'a> Renderer<'a>{
+    pub fn new(filename:&'a str,source:&'a str,color:bool)->Renderer<'a>{
+        Renderer{filename,source,color}
+    }
+
+    fn paint(&self,style:&str,text:&str)->String{
+        if self.color{
+            format!("{}{}{}",style,text,RESET)
+        }
+        else{
+            text.to_string()
+        }
+    }
+
+    fn severity_style(severity:Severity)->&'static str{
+        match severity{
+            Severity::ERROR => RED,
+            Severity::WARNING => YELLOW,
+            Severity::NOTE => GREEN
+        }
+    }
+
+    pub fn render(&self,diagnostic:&Diagnostic)->String{
+        let style = Self::severity_style(diagnostic.severity);
+        let span = diagnostic.span;
+        let mut output = format!("{}{}\n",
+            self.paint(style,&format!("{}[{}]",diagnostic.severity,diagnostic.code)),
+            self.paint(BOLD,&format!(": {}",diagnostic.message)));
+
+        //spans of made up tokens point nowhere , there is no line to show
+        if span.line == 0 || !self.source.is_char_boundary(span.start){
+            output.push_str(&format!("{} {}\n",self.paint(BLUE,"-->"),self.filename));
+            return output + &self.notes(diagnostic,0);
+        }
+
+        let gutter = span.line.to_string().len();
+        let pad = " ".repeat(gutter);
+        let line_start = self.source[..span.start].rfind('\n').map_or(0,|i| i + 1);
+        let line_end = self.source[span.start..].find('\n').map_or(self.source.len(),|i| span.start + i);
+        let line = self.source[line_start..line_end].trim_end_matches('\r');
+
+        //keep tabs so the carets line up with the text above them
+        let indent : String = self.source[line_start..span.start].chars().map(|c| if c == '\t' {'\t'} else {' '}).collect();
+        let mut end = span.end.min(line_start + line.len()).max(span.start);
+        while !self.source.is_char_boundary(end){
+            end += 1;
+        }
+        let underline = "^".repeat(self.source[span.start..end].chars().count().max(1));
+
+        output.push_str(&format!("{}{} {}:{}:{}\n",pad,self.paint(BLUE,"-->"),self.filename,span.line,span.column));
+        output.push_str(&format!("{} {}\n",pad,self.paint(BLUE,"|")));
+        output.push_str(&format!("{} {}\n",self.paint(BLUE,&format!("{} |",span.line)),line));
+        output.push_str(&format!("{} {} {}{}\n",pad,self.paint(BLUE,"|"),indent,self.paint(style,&underline)));
+        output + &self.notes(diagnostic,gutter)
+    }
+
+    fn notes(&self,diagnostic:&Diagnostic,gutter:usize)->String{
+        diagnostic.notes.iter()
+            .map(|note| format!("{} {} {}\n"," ".repeat(gutter),self.paint(BLUE,"="),self.paint(BOLD,&format!("note: {}",note))))
+            .collect()
+    }
+}
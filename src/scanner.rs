@@ -1,11 +1,24 @@
+use crate::diagnostic::*;
 use crate::token::*;
 use crate::token::TokenType::*;
 
+//longest string constant the reference runtime accepts
+pub const MAX_STRING_LENGTH:usize = 1024;
+
 pub struct Scanner{
-    had_error: bool,
+    diagnostics: Diagnostics,
+    strings: Interner,
     line:  usize,
+    line_start: usize,//offset of the first character on the current line
+    line_extra: usize,//bytes taken by multibyte characters on the current line beyond their first , columns count characters
     start: usize,
+    start_line: usize,
+    start_column: usize,
     current: usize,
+    finished: bool,//the EOF token has been handed out
+    trivia: bool,//keep whitespace and comments on the tokens
+    pending: Vec<Trivia>,//trivia scanned since the last token
+    held: Option<Token>,//with trivia the last token waits for the trivia that trails it
     source : String
 }
 
@@ -13,64 +26,168 @@ impl Scanner{
 
     pub fn new(source:String)->Scanner{
         Scanner{
-            had_error:false,
+            diagnostics:Diagnostics::new(),
+            strings:Interner::new(),
             line:1usize,
+            line_start:0usize,
+            line_extra:0usize,
+            start_line:1usize,
+            start_column:1usize,
             source:source.to_string(),
             current:0usize,
+            finished:false,
+            trivia:false,
+            pending:vec![],
+            held:None,
             start:0usize
         }
     }
 
-    fn error(&mut self,line:usize,message:&str){
-        eprintln!("[line {}] Error: {}",line,message);
-        self.had_error = true;
+    //reports the error and returns an ERROR token in its place . the literal of the token is the
+    //message the reference lexer gives for the same error
+    fn error(&mut self,code:&'static str,message:&str,span:Span,literal:&str)->Option<Token>{
+        self.report(Diagnostic::error(code,message.to_string(),span),literal)
+    }
+
+    fn report(&mut self,diagnostic:Diagnostic,literal:&str)->Option<Token>{
+        self.diagnostics.push(diagnostic);
+        let lexeme = self.source[self.start..self.current].to_string();
+        let message = self.strings.intern(literal);
+        Some(Token::new(self.line,self.span(),lexeme,ERROR,Some(Literal::STR(message))))
+    }
+
+    //tokens for source without lexical errors
+    pub fn scan_tokens(&mut self)->Result<Vec<Token>,Diagnostics>{
+        let (tokens,diagnostics) = self.scan_all();
+        if diagnostics.has_errors(){
+            Err(diagnostics)
+        }
+        else{
+            Ok(tokens)
+        }
     }
 
-    pub fn scan_tokens(&mut self)->Result<Vec<Token>,&str>{
-        self.line  = 1;
-        let mut tokens : Vec<Token> = Vec::new();
-        
+    //every token , with an ERROR token wherever the source has a lexical error , and the diagnostics
+    //for those errors . this is what the --lex output is made from
+    pub fn scan_all(&mut self)->(Vec<Token>,Diagnostics){
+        let tokens : Vec<Token> = self.by_ref().collect();
+        (tokens,self.take_diagnostics())
+    }
+
+    //keep whitespace and comments as trivia on the tokens , the source can then be rebuilt from them
+    //with Token::source_text
+    pub fn with_trivia(mut self)->Scanner{
+        self.trivia = true;
+        self
+    }
+
+    //the next token , ending with a single EOF token . lexical errors come out as ERROR tokens and
+    //their diagnostics pile up until taken
+    pub fn next_token(&mut self)->Option<Token>{
+        let next = self.scan_next();
+        if !self.trivia{
+            return next;
+        }
+        let mut token = match next{
+            Some(token) => token,
+            None => return self.held.take()
+        };
+        //trivia up to the first line break trails the previous token , the rest leads this one
+        let split = match self.held{
+            Some(_) => self.pending.iter().position(|t| t.kind == TriviaKind::NEWLINE).unwrap_or(self.pending.len()),
+            None => 0
+        };
+        token.leading = self.pending.split_off(split);
+        match self.held.replace(token){
+            Some(mut previous) =>{
+                previous.trailing = std::mem::take(&mut self.pending);
+                Some(previous)
+            },
+            None => self.next_token()
+        }
+    }
 
+    fn scan_next(&mut self)->Option<Token>{
         while !self.eof() {
             self.start = self.current;
-            let scanned_token = self.scan_token();
-            match scanned_token{
-                Some(token) => tokens.push(token),
-                None => continue
+            self.start_line = self.line;
+            self.start_column = self.column(self.start);
+            if let Some(token) = self.scan_token(){
+                return Some(token);
+            }
+            if self.trivia{
+                self.keep_trivia();
             }
         }
-
-        tokens.push(
-            Token::new(
-                self.line,
-                "\0".to_string(),
-                TokenType::EOF,
-                None
-            )
-        );
-        if self.had_error{
-            Err("Error in scanning")
+        if self.finished{
+            return None;
         }
-        else{
-            Ok(tokens)
+        self.finished = true;
+        Some(Token::new(
+            self.line,
+            Span::new(self.source.len(),self.source.len(),self.line,self.column(self.source.len())),
+            "\0".to_string(),
+            TokenType::EOF,
+            None
+        ))
+    }
+
+    //what scan_token just skipped , runs of blanks are kept as one piece
+    fn keep_trivia(&mut self){
+        let text = &self.source[self.start..self.current];
+        let kind = if text == "\n" {TriviaKind::NEWLINE}
+            else if text.starts_with("--") {TriviaKind::LINECOMMENT}
+            else if text.starts_with("(*") {TriviaKind::BLOCKCOMMENT}
+            else {TriviaKind::WHITESPACE};
+        if let Some(last) = self.pending.last_mut(){
+            if kind == TriviaKind::WHITESPACE && last.kind == TriviaKind::WHITESPACE{
+                last.text.push_str(text);
+                last.span.end = self.current;
+                return;
+            }
         }
+        self.pending.push(Trivia{kind,text:text.to_string(),span:self.span()});
+    }
+
+    //diagnostics for the tokens scanned so far
+    pub fn take_diagnostics(&mut self)->Diagnostics{
+        std::mem::take(&mut self.diagnostics)
     }
 
-   fn add_token(&mut self,tokentype:TokenType,literal:Option<String>)->Option<Token>{
-        Some(Token::new(self.line,self.source[self.start..self.current].trim().to_string(),tokentype,literal))
+   fn add_token(&mut self,tokentype:TokenType,literal:Option<Literal>)->Option<Token>{
+        Some(Token::new(self.line,self.span(),self.source[self.start..self.current].to_string(),tokentype,literal))
+   }
+
+   fn span(&self)->Span{
+        Span::new(self.start,self.current,self.start_line,self.start_column)
+   }
+
+   //called with the newline just consumed
+   fn newline(&mut self){
+        self.line += 1;
+        self.line_start = self.current;
+        self.line_extra = 0;
+   }
+
+   //column of a byte offset on the current line , counted in characters
+   fn column(&self,offset:usize)->usize{
+        offset - self.line_start - self.line_extra + 1
    }
 
     fn scan_token(&mut self)->Option<Token>{
-        let c = self.advance();
+        let c = self.advance()?;
         match  c {
             '\n' => {
-                self.line = self.line + 1;
+                self.newline();
                 None
             },
             '(' => {
-                if self.check_next('*'){
-                    self.block_comment();
-                    None
+                if self.match_char('*'){
+                    let span = self.span();
+                    match self.block_comment(){
+                        true => None,
+                        false => self.error(UNTERMINATED_COMMENT,"Unterminated block comment",span,"EOF in comment")
+                    }
                 }
                 else{
                     self.add_token(LEFTPAREN, None)
@@ -88,7 +205,7 @@ impl Scanner{
             '~' => self.add_token(TILDA, None),
             '+' =>self.add_token(PLUS, None),
             '-' =>{
-                if self.check_next('-'){
+                if self.match_char('-'){
                     self.comment();
                     None
                 }
@@ -96,11 +213,17 @@ impl Scanner{
                     self.add_token(MINUS, None)
                 }
             },
-            '*' =>self.add_token(STAR, None),
+            '*' =>{
+                if self.match_char(')'){
+                    self.error(UNMATCHED_COMMENT,"Unmatched *) outside of a comment",self.span(),"Unmatched *)")
+                }
+                else{
+                    self.add_token(STAR, None)
+                }
+            },
             '/' =>self.add_token(SLASH, None) ,
             '=' =>{
-                if self.check_next('>'){
-                    self.advance();
+                if self.match_char('>'){
                     self.add_token(RARROW, None)
                 }
                 else{
@@ -109,8 +232,7 @@ impl Scanner{
             },
             '>' =>
             {
-                if self.check_next('='){
-                    self.advance();
+                if self.match_char('='){
                     self.add_token(MOREEQUAL, None)
                 }
                 else{
@@ -118,19 +240,17 @@ impl Scanner{
                 }
             },
             '<' =>{
-                if self.check_next('='){
-                    self.advance();
+                if self.match_char('='){
                     self.add_token(LESSEQUAL, None)
                 }
-                else if self.check_next('-'){
-                    self.advance();
+                else if self.match_char('-'){
                     self.add_token(ASSIGN, None)
                 }
                 else{
                     self.add_token(LESSTHAN, None)
                 }
             },
-            '\"'=>{self.advance();self.string_token()},
+            '\"'=>self.string_token(),
             ' ' | '\r' | '\t' => None,
             _ =>{
                 if Self::is_alpha(c) {
@@ -141,125 +261,199 @@ impl Scanner{
                 }
                
                 else{
-                    self.error(self.line,&format!("Unexpected character '{}' ",c));
-                   None
+                    self.invalid_character(c)
                 }
             }
          }
 
     }
 
-    fn peek(&mut self)->char{
-        match self.source.chars().nth(self.current-1){
-            None =>'`',
-            Some(value) => value
-        }
-    }
-    fn peek_next(&mut self)->char{
-        match self.source.chars().nth(self.current){
-            None =>'`',
-            Some(value) => value
-        }
+    //current is a byte offset into the source , every character is looked at a constant number of times
+    fn peek(&self)->Option<char>{
+        self.source[self.current..].chars().next()
     }
 
-    fn check(&mut self,c:char)->bool{
-      !self.eof() && c == self.peek() 
+    fn check(&self,c:char)->bool{
+        self.peek() == Some(c)
     }
 
-    fn check_next(&mut self,c:char)->bool{
-       !self.eof()&& c==self.peek_next()
+    //consumes the next character if it is c
+    fn match_char(&mut self,c:char)->bool{
+        if self.check(c){
+            self.current += c.len_utf8();
+            return true;
+        }
+        false
     }
 
-    fn advance(&mut self)->char{
-        self.current = self.current + 1;
-        match self.source.chars().nth(self.current-1){
-            None =>'`',
-            Some(value) => value
-        }
+    fn advance(&mut self)->Option<char>{
+        let c = self.peek()?;
+        self.current += c.len_utf8();
+        self.line_extra += c.len_utf8() - 1;
+        Some(c)
     }
 
     fn identifier(&mut self)->Option<Token>{
-        while !self.eof() && Self::is_alphanumeric(self.peek()){
+        while self.peek().is_some_and(Self::is_alphanumeric){
             self.advance();
         }
-        self.current = self.current-1;
-        let lexeme :&str = self.source[self.start..self.current].trim();
+        let lexeme :&str = &self.source[self.start..self.current];
+        //true and false have to start with a lowercase letter , True is a type
         let tokentype :TokenType = match Token::keyword(lexeme.to_lowercase().as_str()) {
+          Some(KEYTRUE|KEYFALSE) if lexeme.starts_with(|c:char| c.is_ascii_uppercase()) => TYPEID,
           Some(value) => value,
-          None => IDENTIFIER  
+          None if lexeme.starts_with(|c:char| c.is_ascii_uppercase()) => TYPEID,
+          None => OBJECTID
+        };
+        let literal = match tokentype{
+            KEYTRUE => Some(Literal::BOOL(true)),
+            KEYFALSE => Some(Literal::BOOL(false)),
+            _ => None
         };
-        self.add_token(tokentype, Some(lexeme.to_string()))
+        self.add_token(tokentype, literal)
     }
 
     fn number(&mut self)->Option<Token>{
-        while !self.eof() && Self::is_digit(self.peek()){
+        while self.peek().is_some_and(Self::is_digit){
             self.advance();
         }
-        self.current = self.current - 1;
-        self.add_token(INTEGER, Some(self.source[self.start..self.current].to_string()))
+        //cool has no negative literals , ~ is an operator , so the largest Int is the limit
+        match self.source[self.start..self.current].parse::<i32>(){
+            Ok(value) => self.add_token(INTEGER, Some(Literal::INT(value))),
+            Err(_) =>{
+                let message = format!("Integer constant {} does not fit in a 32 bit Int",&self.source[self.start..self.current]);
+                self.error(INTEGER_OUT_OF_RANGE,&message,self.span(),"Integer constant out of range")
+            }
+        }
     }
 
+    //the cursor is just past the opening quote . escapes are decoded as the string is read and the
+    //decoded value becomes the literal of the token , the lexeme is the string as written (quotes included).
+    //after a null character or an overlong string the rest of the string is skipped , the lexer goes on
+    //after the closing quote or the unescaped newline that ends it
     fn string_token(&mut self)->Option<Token>{
-        while !self.eof() && !self.check('\"') {
-            if self.check('\\'){
-                self.advance();
+        let mut value = String::new();
+        let mut length = 0usize;
+        let mut null : Option<(Span,&str)> = None;
+        loop{
+            let c = match self.advance(){
+                Some(c) => c,
+                None => return self.error(UNTERMINATED_STRING,"Unterminated string",self.span(),"EOF in string constant")
+            };
+            let decoded = match c{
+                '\"' => break,
+                '\n' =>{
+                    let span = Span::new(self.start,self.current - 1,self.start_line,self.start_column);
+                    self.newline();
+                    //a null before the newline is the first error , the reference scanner reports it
+                    if let Some((span,literal)) = null{
+                        return self.error(NULL_IN_STRING,"Null character in string",span,literal);
+                    }
+                    return self.error(UNTERMINATED_STRING,"Unterminated string , a newline in a string has to be escaped",span,"Unterminated string constant");
+                },
+                '\0' =>{
+                    if null.is_none(){
+                        null = Some((self.char_span(1),"String contains null character."));
+                    }
+                    continue;
+                },
+                '\\' => match self.advance(){
+                    None => return self.error(UNTERMINATED_STRING,"Unterminated string",self.span(),"EOF in string constant"),
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('\0') =>{
+                        if null.is_none(){
+                            null = Some((self.char_span(2),"String contains escaped null character."));
+                        }
+                        continue;
+                    },
+                    Some('\n') =>{
+                        self.newline();
+                        '\n'
+                    },
+                    Some(other) => other
+                },
+                other => other
+            };
+            length += 1;
+            if length <= MAX_STRING_LENGTH{
+                value.push(decoded);
             }
-            if self.check('\0') {self.error(self.line,"null character in string");}
-            if self.check('\n'){
-                self.line = self.line + 1;
-            }
-            self.advance();
         }
-        if self.eof() {
-            self.error(self.line,"Unterminated string");
-            return  self.add_token(ERROR, None);
+        if let Some((span,literal)) = null{
+            return self.error(NULL_IN_STRING,"Null character in string",span,literal);
         }
-       self.start = self.start + 1;
-       self.current = self.current - 1;
-       let tok = self.add_token(STRING, Some(self.source[self.start..self.current].to_string()));
-       self.advance();
-       tok
+        if length > MAX_STRING_LENGTH{
+            let message = format!("String constant is {} characters long , the limit is {}",length,MAX_STRING_LENGTH);
+            return self.error(STRING_TOO_LONG,&message,self.span(),"String constant too long");
+        }
+        let lexeme = self.source[self.start..self.current].to_string();
+        let value = self.strings.intern(&value);
+        Some(Token::new(self.line,self.span(),lexeme,STRING,Some(Literal::STR(value))))
+    }
+
+    //span of the last width bytes read
+    fn char_span(&self,width:usize)->Span{
+        Span::new(self.current - width,self.current,self.line,self.column(self.current - width))
+    }
+
+    //anything outside strings and comments that cannot start a token , which includes every non ascii character
+    fn invalid_character(&mut self,c:char)->Option<Token>{
+        let message = format!("Invalid character '{}' (U+{:04X})",c.escape_debug(),c as u32);
+        let mut diagnostic = Diagnostic::error(INVALID_CHARACTER,message,self.span());
+        if !c.is_ascii(){
+            diagnostic = diagnostic.with_note("characters outside of ascii are only allowed in strings and comments".to_string());
+        }
+        self.report(diagnostic,&c.to_string())
     }
 
     fn is_digit(c : char)->bool{
-        c>='0' && c<='9'    
+        c.is_ascii_digit()    
     }
+    //identifiers start with a letter , a leading _ is an invalid character like in the reference lexer
     fn is_alpha(c:char)->bool{
-        (c>='a' && c<='z')||(c=='_')||(c>='A' && c<='Z')
+        c.is_ascii_lowercase()||c.is_ascii_uppercase()
     }
     fn is_alphanumeric(c:char)->bool{
-        Self::is_alpha(c)||Self::is_digit(c)
+        Self::is_alpha(c)||Self::is_digit(c)||(c=='_')
     }
+    //the newline ending the comment is left for scan_token
     fn comment(&mut self){
-        while !self.eof() && !self.check('\n') {
+        while self.peek().is_some_and(|c| c != '\n'){
             self.advance();
-        }   
-        if self.check('\n'){self.line = self.line + 1;}
-        
+        }
     }
-    fn block_comment(&mut self){
-        let line = self.line;
-        self.advance();
-        while !self.eof(){
-            if self.check('\n'){
-                self.line = self.line + 1;
-            }
-            if self.check('(')&&self.check_next('*'){
-                self.block_comment();
+    //the opening (* is already consumed . false when the source ends inside the comment
+    fn block_comment(&mut self)->bool{
+        let mut depth = 1usize;
+        while let Some(c) = self.advance(){
+            match c{
+                '\n' => self.newline(),
+                '(' if self.match_char('*') => depth += 1,
+                '*' if self.match_char(')') =>{
+                    depth -= 1;
+                    if depth == 0{
+                        return true;
+                    }
+                },
+                _ => ()
             }
-            if self.check('*') && self.check_next(')'){
-                self.advance();
-                return;
-            }
-            self.advance();
-            
         }
-        self.error(line,"Unterminated block comment");
-        
+        false
     }
     
-    fn eof(&mut self)->bool{
+    fn eof(&self)->bool{
         self.current >= self.source.len()  
     }
 
-}
\ No newline at end of file
+}
+
+//tokens are scanned on demand , a parser can read them straight from the scanner
+impl Iterator for Scanner{
+    type Item = Token;
+    fn next(&mut self)->Option<Token>{
+        self.next_token()
+    }
+}
@@ -0,0 +1,839 @@
+use std::collections::{HashMap,HashSet};
+
+use crate::ast::*;
+use crate::token::{Token,TokenType};
+
+/*
+semantic analysis
+
+the class table is built first . it installs the basic classes (Object, IO, Int, String, Bool),
+records every user defined class and checks the inheritance graph:
+
+- a class is defined only once and basic classes are not redefined
+- every parent class exists
+- nobody inherits from Int, String, Bool or SELF_TYPE
+- there are no inheritance cycles
+- a Main class exists
+
+classes can be declared before their parents, so every class is recorded before any parent is resolved.
+once the graph is known to be a tree the features are collected parents first , so overriding methods
+and inherited attributes can be checked against what the ancestors declare.
+
+the type checker then walks every class with the rules from section 12 of the cool manual.
+an expression that cannot be typed gets the type Object so that one mistake does not cascade.
+*/
+
+pub const OBJECT:&str = "Object";
+pub const IO:&str = "IO";
+pub const INT:&str = "Int";
+pub const STRING:&str = "String";
+pub const BOOL:&str = "Bool";
+pub const SELF_TYPE:&str = "SELF_TYPE";
+pub const MAIN:&str = "Main";
+pub const BASIC_CLASSES:[&str;5] = [OBJECT,IO,INT,STRING,BOOL];
+
+#[derive(Debug,Clone)]
+pub struct SemantError{
+    pub filename : String,
+    pub line : usize,
+    pub message : String
+}
+
+impl SemantError{
+    pub fn new(filename:&str,line:usize,message:String)->SemantError{
+        SemantError{filename:filename.to_string(),line,message}
+    }
+
+    //errors on basic classes or the program as a whole have no line , just like coolc
+    pub fn report(&self)->String{
+        if self.line == 0{
+            self.message.clone()
+        }
+        else{
+            format!("{}:{}: {}",self.filename,self.line,self.message)
+        }
+    }
+}
+
+#[derive(Debug,Clone)]
+pub struct MethodSig{
+    pub name : String,
+    pub formals : Vec<(String,String)>,//(name,type)
+    pub return_type : String,
+    pub line : usize
+}
+
+#[derive(Debug,Clone)]
+pub struct AttrSig{
+    pub name : String,
+    pub type_ : String,
+    pub line : usize
+}
+
+#[derive(Debug)]
+pub struct ClassInfo{
+    pub parent : Option<String>,
+    pub filename : String,
+    pub line : usize,
+    pub basic : bool,
+    pub attributes : Vec<AttrSig>,//only the ones declared in this class , in order
+    pub methods : Vec<MethodSig>
+}
+
+//static type of the receiver of every dispatch that names one , keyed by the id of the dispatch
+pub type TypeMap = HashMap<NodeId,String>;
+
+//everything later stages need to know about a program that passed semantic analysis
+pub struct Analysis{
+    pub table : ClassTable,
+    pub types : TypeMap
+}
+
+#[derive(Debug)]
+pub struct ClassTable{
+    classes : HashMap<String,ClassInfo>,
+    order : Vec<String>,//parents always come before their children
+    errors : Vec<SemantError>,
+    filename : String//file of the class being installed , for errors
+}
+
+//runs the whole semantic analysis
+pub fn check(program:&Program)->Result<Analysis,Vec<SemantError>>{
+    let table = ClassTable::new(program);
+    //without a well formed inheritance graph there is no order and nothing can be typed
+    if table.order.is_empty(){
+        return Err(table.errors);
+    }
+    let mut checker = TypeChecker::new(&table);
+    checker.errors = table.errors.clone();
+    program.accept(&mut checker);
+    if checker.errors.is_empty(){
+        let types = checker.types;
+        Ok(Analysis{table,types})
+    }
+    else{
+        Err(checker.errors)
+    }
+}
+
+impl ClassTable{
+
+    pub fn new(program:&Program)->ClassTable{
+        let mut table = ClassTable{
+            classes:HashMap::new(),
+            order:vec![],
+            errors:vec![],
+            filename:String::new()
+        };
+        table.install_basic_classes();
+        let user_classes = table.install_user_classes(program);
+        table.check_parents(&user_classes);
+        table.check_cycles(&user_classes);
+        if !table.classes.contains_key(MAIN){
+            table.error(0,"Class Main is not defined.".to_string());
+        }
+        if table.has_errors(){
+            return table;
+        }
+        table.order = table.inheritance_order(&user_classes);
+        table.install_features(program);
+        table
+    }
+
+    fn error(&mut self,line:usize,message:String){
+        self.errors.push(SemantError::new(&self.filename,line,message));
+    }
+
+    //errors from here on are reported against the file of the class
+    fn enter(&mut self,class:&str){
+        self.filename = self.classes.get(class).map_or(String::new(),|c| c.filename.clone());
+    }
+
+    fn install_basic_classes(&mut self){
+        self.add_class(OBJECT,None,"",0,true);
+        for name in BASIC_CLASSES[1..].iter(){
+            self.add_class(name,Some(OBJECT),"",0,true);
+        }
+        let basic_methods = [
+            (OBJECT,"abort",vec![],OBJECT),
+            (OBJECT,"type_name",vec![],STRING),
+            (OBJECT,"copy",vec![],SELF_TYPE),
+            (IO,"out_string",vec![("x",STRING)],SELF_TYPE),
+            (IO,"out_int",vec![("x",INT)],SELF_TYPE),
+            (IO,"in_string",vec![],STRING),
+            (IO,"in_int",vec![],INT),
+            (STRING,"length",vec![],INT),
+            (STRING,"concat",vec![("s",STRING)],STRING),
+            (STRING,"substr",vec![("i",INT),("l",INT)],STRING)
+        ];
+        for (class,name,formals,return_type) in basic_methods{
+            let method = MethodSig{
+                name:name.to_string(),
+                formals:formals.iter().map(|(id,type_)| (id.to_string(),type_.to_string())).collect(),
+                return_type:return_type.to_string(),
+                line:0
+            };
+            self.classes.get_mut(class).unwrap().methods.push(method);
+        }
+    }
+
+    fn add_class(&mut self,name:&str,parent:Option<&str>,filename:&str,line:usize,basic:bool){
+        self.classes.insert(name.to_string(),ClassInfo{
+            parent:parent.map(|p| p.to_string()),
+            filename:filename.to_string(),
+            line,
+            basic,
+            attributes:vec![],
+            methods:vec![]
+        });
+    }
+
+    //returns the names of the user classes that made it into the table , in program order
+    fn install_user_classes(&mut self,program:&Program)->Vec<String>{
+        let mut installed = vec![];
+        for class in program.classes(){
+            let name = &class.type_().lexeme;
+            let line = class.type_().line;
+            self.filename = class.filename().to_string();
+            if name == SELF_TYPE || self.classes.get(name).is_some_and(|c| c.basic){
+                self.error(line,format!("Redefinition of basic class {}.",name));
+                continue;
+            }
+            if self.classes.contains_key(name){
+                self.error(line,format!("Class {} was previously defined.",name));
+                continue;
+            }
+            let parent = match class.inherits(){
+                Some(parent) => parent.lexeme.as_str(),
+                None => OBJECT
+            };
+            self.add_class(name,Some(parent),class.filename(),line,false);
+            installed.push(name.clone());
+        }
+        installed
+    }
+
+    fn check_parents(&mut self,user_classes:&[String]){
+        for name in user_classes{
+            self.enter(name);
+            let class = &self.classes[name];
+            let line = class.line;
+            let parent = class.parent.clone().unwrap();
+            match parent.as_str(){
+                INT|STRING|BOOL|SELF_TYPE => {
+                    self.error(line,format!("Class {} cannot inherit class {}.",name,parent));
+                }
+                _ if !self.classes.contains_key(&parent) => {
+                    self.error(line,format!("Class {} inherits from an undefined class {}.",name,parent));
+                }
+                _ => ()
+            }
+        }
+    }
+
+    //a class whose chain of parents never reaches Object is either in a cycle or below one.
+    //chains that stop at an undefined parent were already reported by check_parents
+    fn check_cycles(&mut self,user_classes:&[String]){
+        for name in user_classes{
+            let mut seen : HashSet<&str> = HashSet::new();
+            let mut current = name.as_str();
+            let mut cyclic = false;
+            while let Some(parent) = self.classes.get(current).and_then(|c| c.parent.as_deref()){
+                if !seen.insert(current){
+                    cyclic = true;
+                    break;
+                }
+                current = parent;
+            }
+            if cyclic{
+                self.enter(name);
+                let line = self.classes[name].line;
+                self.error(line,format!("Class {}, or an ancestor of {}, is involved in an inheritance cycle.",name,name));
+            }
+        }
+    }
+
+    //depth first walk from Object so that every subtree gets a contiguous range in the order.
+    //children are visited in the order they were defined , the basic classes first
+    fn inheritance_order(&self,user_classes:&[String])->Vec<String>{
+        let mut children : HashMap<&str,Vec<&str>> = HashMap::new();
+        for name in BASIC_CLASSES.iter().copied().chain(user_classes.iter().map(|c| c.as_str())){
+            if let Some(parent) = &self.classes[name].parent{
+                children.entry(parent.as_str()).or_default().push(name);
+            }
+        }
+        let mut order = vec![];
+        let mut stack = vec![OBJECT];
+        while let Some(name) = stack.pop(){
+            order.push(name.to_string());
+            if let Some(kids) = children.get(name){
+                stack.extend(kids.iter().rev());
+            }
+        }
+        order
+    }
+
+    fn install_features(&mut self,program:&Program){
+        let mut definitions : HashMap<&str,&Class> = HashMap::new();
+        for class in program.classes(){
+            //a redefined class was already reported , the first definition is the one in the table
+            definitions.entry(class.type_().lexeme.as_str()).or_insert(class);
+        }
+        for name in self.order.clone(){
+            if let Some(class) = definitions.get(name.as_str()){
+                self.enter(&name);
+                for feature in class.features(){
+                    match feature{
+                        Feature::Method{id,type_,parameters,..} => self.install_method(&name,id,type_,parameters),
+                        Feature::Attribute{id,type_,..} => self.install_attribute(&name,id,type_)
+                    }
+                }
+            }
+        }
+        match self.find_method(MAIN,"main"){
+            Some(_) => (),
+            None =>{
+                self.enter(MAIN);
+                let line = self.classes[MAIN].line;
+                self.error(line,"No 'main' method in class Main.".to_string());
+            }
+        }
+    }
+
+    fn install_method(&mut self,class:&str,id:&Token,type_:&Token,parameters:&Vec<Formal>){
+        let name = &id.lexeme;
+        if self.classes[class].methods.iter().any(|m| &m.name == name){
+            self.error(id.line,format!("Method {} is multiply defined.",name));
+            return;
+        }
+        let mut formals : Vec<(String,String)> = vec![];
+        for formal in parameters{
+            let (formal_id,formal_type) = (&formal.id().lexeme,&formal.type_().lexeme);
+            let line = formal.id().line;
+            if formal_id == "self"{
+                self.error(line,"'self' cannot be the name of a formal parameter.".to_string());
+            }
+            else if formals.iter().any(|(id,_)| id == formal_id){
+                self.error(line,format!("Formal parameter {} is multiply defined.",formal_id));
+            }
+            if formal_type == SELF_TYPE{
+                self.error(line,format!("Formal parameter {} cannot have type SELF_TYPE.",formal_id));
+            }
+            else if !self.is_defined(formal_type){
+                self.error(line,format!("Class {} of formal parameter {} is undefined.",formal_type,formal_id));
+            }
+            formals.push((formal_id.clone(),formal_type.clone()));
+        }
+        if !self.is_defined(&type_.lexeme){
+            self.error(type_.line,format!("Undefined return type {} in method {}.",type_.lexeme,name));
+        }
+        let parent = self.classes[class].parent.clone().unwrap();
+        if let Some(inherited) = self.find_method(&parent,name).cloned(){
+            if inherited.formals.len() != formals.len(){
+                self.error(id.line,format!("Incompatible number of formal parameters in redefined method {}.",name));
+            }
+            else{
+                for ((_,new_type),(_,old_type)) in formals.iter().zip(inherited.formals.iter()){
+                    if new_type != old_type{
+                        self.error(id.line,format!("In redefined method {}, parameter type {} is different from original type {}",name,new_type,old_type));
+                    }
+                }
+            }
+            if inherited.return_type != type_.lexeme{
+                self.error(id.line,format!("In redefined method {}, return type {} is different from original return type {}.",name,type_.lexeme,inherited.return_type));
+            }
+        }
+        let method = MethodSig{
+            name:name.clone(),
+            formals,
+            return_type:type_.lexeme.clone(),
+            line:id.line
+        };
+        self.classes.get_mut(class).unwrap().methods.push(method);
+    }
+
+    fn install_attribute(&mut self,class:&str,id:&Token,type_:&Token){
+        let name = &id.lexeme;
+        if name == "self"{
+            self.error(id.line,"'self' cannot be the name of an attribute.".to_string());
+            return;
+        }
+        if self.classes[class].attributes.iter().any(|a| &a.name == name){
+            self.error(id.line,format!("Attribute {} is multiply defined in class.",name));
+            return;
+        }
+        let parent = self.classes[class].parent.clone().unwrap();
+        if self.find_attribute(&parent,name).is_some(){
+            self.error(id.line,format!("Attribute {} is an attribute of an inherited class.",name));
+            return;
+        }
+        if !self.is_defined(&type_.lexeme){
+            self.error(type_.line,format!("Class {} of attribute {} is undefined.",type_.lexeme,name));
+        }
+        let attribute = AttrSig{
+            name:name.clone(),
+            type_:type_.lexeme.clone(),
+            line:id.line
+        };
+        self.classes.get_mut(class).unwrap().attributes.push(attribute);
+    }
+
+    pub fn get(&self,name:&str)->Option<&ClassInfo>{
+        self.classes.get(name)
+    }
+
+    pub fn order(&self)->&Vec<String>{
+        &self.order
+    }
+
+    pub fn is_defined(&self,type_:&str)->bool{
+        type_ == SELF_TYPE || self.classes.contains_key(type_)
+    }
+
+    //the class itself followed by its parent , grandparent and so on up to Object
+    pub fn ancestors(&self,name:&str)->Vec<&str>{
+        let mut ancestors = vec![];
+        let mut current = self.classes.get_key_value(name);
+        while let Some((name,class)) = current{
+            ancestors.push(name.as_str());
+            current = class.parent.as_ref().and_then(|p| self.classes.get_key_value(p));
+        }
+        ancestors
+    }
+
+    pub fn find_method(&self,class:&str,method:&str)->Option<&MethodSig>{
+        self.ancestors(class).into_iter()
+            .find_map(|c| self.classes[c].methods.iter().find(|m| m.name == method))
+    }
+
+    pub fn find_attribute(&self,class:&str,attribute:&str)->Option<&AttrSig>{
+        self.ancestors(class).into_iter()
+            .find_map(|c| self.classes[c].attributes.iter().find(|a| a.name == attribute))
+    }
+
+    //every attribute an object of the class carries , inherited ones first . this is the object layout
+    pub fn all_attributes(&self,class:&str)->Vec<&AttrSig>{
+        self.ancestors(class).into_iter().rev()
+            .flat_map(|c| self.classes[c].attributes.iter())
+            .collect()
+    }
+
+    //does child conform to parent . SELF_TYPE has to be resolved by the caller
+    pub fn conforms(&self,child:&str,parent:&str)->bool{
+        self.ancestors(child).contains(&parent)
+    }
+
+    //least upper bound , the closest common ancestor of both classes
+    pub fn lub(&self,a:&str,b:&str)->String{
+        let b_ancestors = self.ancestors(b);
+        self.ancestors(a).into_iter()
+            .find(|c| b_ancestors.contains(c))
+            .unwrap_or(OBJECT)
+            .to_string()
+    }
+
+    pub fn errors(&self)->&Vec<SemantError>{
+        &self.errors
+    }
+
+    pub fn has_errors(&self)->bool{
+        !self.errors.is_empty()
+    }
+}
+
+pub struct TypeChecker<'a>{
+    table : &'a ClassTable,
+    class : String,//class whose features are being checked , SELF_TYPE stands for it
+    scopes : Vec<HashMap<String,String>>,
+    types : TypeMap,
+    errors : Vec<SemantError>
+}
+
+impl<'a> TypeChecker<'a>{
+
+    pub fn new(table:&'a ClassTable)->TypeChecker<'a>{
+        TypeChecker{
+            table,
+            class:OBJECT.to_string(),
+            scopes:vec![],
+            types:HashMap::new(),
+            errors:vec![]
+        }
+    }
+
+    fn type_of(&mut self,expr:&Expr)->String{
+        expr.accept(self)
+    }
+
+    fn error(&mut self,line:usize,message:String){
+        let filename = self.table.get(&self.class).map_or("",|c| c.filename.as_str());
+        self.errors.push(SemantError::new(filename,line,message));
+    }
+
+    fn bind(&mut self,id:&str,type_:&str){
+        self.scopes.last_mut().unwrap().insert(id.to_string(),type_.to_string());
+    }
+
+    //let , case and formals shadow attributes , inner scopes shadow outer ones
+    fn lookup(&self,id:&str)->Option<String>{
+        if id == "self"{
+            return Some(SELF_TYPE.to_string());
+        }
+        self.scopes.iter().rev()
+            .find_map(|scope| scope.get(id).cloned())
+            .or_else(|| self.table.find_attribute(&self.class,id).map(|a| a.type_.clone()))
+    }
+
+    fn resolve<'t>(&'t self,type_:&'t str)->&'t str{
+        if type_ == SELF_TYPE {&self.class} else {type_}
+    }
+
+    fn conforms(&self,child:&str,parent:&str)->bool{
+        if child == parent{
+            return true;
+        }
+        if parent == SELF_TYPE{
+            return false;
+        }
+        self.table.conforms(self.resolve(child),parent)
+    }
+
+    fn lub(&self,a:&str,b:&str)->String{
+        if a == b{
+            return a.to_string();
+        }
+        self.table.lub(self.resolve(a),self.resolve(b))
+    }
+
+    //types that name no class were already reported while building the class table
+    fn check_defined(&self,type_:&str)->String{
+        if self.table.is_defined(type_) {type_.to_string()} else {OBJECT.to_string()}
+    }
+
+    fn check_int_operands(&mut self,left:&Expr,operator:&Token,right:&Expr,result:&str)->String{
+        let left_type = self.type_of(left);
+        let right_type = self.type_of(right);
+        if left_type != INT || right_type != INT{
+            self.error(operator.line,format!("non-Int arguments: {} {} {}",left_type,operator.lexeme,right_type));
+        }
+        result.to_string()
+    }
+}
+
+impl Visitor<String> for TypeChecker<'_>{
+
+    fn visit_program(&mut self,classes:&Vec<Class>)->String{
+        for class in classes{
+            class.accept(self);
+        }
+        OBJECT.to_string()
+    }
+
+    fn visit_class(&mut self,type_:&Token,_inherits:&Option<Token>,features:&Vec<Feature>)->String{
+        self.class = type_.lexeme.clone();
+        for feature in features{
+            feature.accept(self);
+        }
+        self.class.clone()
+    }
+
+    fn visit_attribute(&mut self,id:&Token,type_:&Token,expr:&Option<Expr>)->String{
+        let declared = self.check_defined(&type_.lexeme);
+        if let Some(expr) = expr{
+            self.scopes.push(HashMap::new());
+            let inferred = self.type_of(expr);
+            self.scopes.pop();
+            if !self.conforms(&inferred,&declared){
+                self.error(id.line,format!("Inferred type {} of initialization of attribute {} does not conform to declared type {}.",inferred,id.lexeme,declared));
+            }
+        }
+        declared
+    }
+
+    fn visit_method(&mut self,id:&Token,type_:&Token,parameters:&Vec<Formal>,body:&Expr)->String{
+        self.scopes.push(HashMap::new());
+        for parameter in parameters{
+            parameter.accept(self);
+        }
+        let declared = self.check_defined(&type_.lexeme);
+        let inferred = self.type_of(body);
+        self.scopes.pop();
+        if !self.conforms(&inferred,&declared){
+            self.error(body.line(),format!("Inferred return type {} of method {} does not conform to declared return type {}.",inferred,id.lexeme,declared));
+        }
+        declared
+    }
+
+    //bad formals were reported by the class table , they are still bound so the body can be checked
+    fn visit_formal(&mut self,id:&Token,type_:&Token)->String{
+        let type_ = self.check_defined(&type_.lexeme);
+        if id.lexeme != "self"{
+            self.bind(&id.lexeme,&type_);
+        }
+        type_
+    }
+
+    fn visit_expr(&mut self,expr:&Expr)->String{
+        self.type_of(expr)
+    }
+
+    fn visit_assign(&mut self,left:&Expr,right:&Expr)->String{
+        let inferred = self.type_of(right);
+        let id = match left{
+            Expr::ID{id} => id,
+            _ => return inferred
+        };
+        if id.lexeme == "self"{
+            self.error(id.line,"Cannot assign to 'self'.".to_string());
+            return inferred;
+        }
+        match self.lookup(&id.lexeme){
+            Some(declared) =>{
+                if !self.conforms(&inferred,&declared){
+                    self.error(id.line,format!("Type {} of assigned expression does not conform to declared type {} of identifier {}.",inferred,declared,id.lexeme));
+                }
+            }
+            None => self.error(id.line,format!("Assignment to undeclared variable {}.",id.lexeme))
+        }
+        inferred
+    }
+
+    fn visit_arithmetic(&mut self,left:&Expr,operator:&Token,right:&Expr)->String{
+        self.check_int_operands(left,operator,right,INT)
+    }
+
+    fn visit_factor(&mut self,left:&Expr,operator:&Token,right:&Expr)->String{
+        self.check_int_operands(left,operator,right,INT)
+    }
+
+    fn visit_comparison(&mut self,left:&Expr,operator:&Token,right:&Expr)->String{
+        if operator.tokentype != TokenType::EQUALITY{
+            return self.check_int_operands(left,operator,right,BOOL);
+        }
+        let left_type = self.type_of(left);
+        let right_type = self.type_of(right);
+        let basic = [INT,STRING,BOOL];
+        if left_type != right_type && (basic.contains(&left_type.as_str()) || basic.contains(&right_type.as_str())){
+            self.error(operator.line,"Illegal comparison with a basic type.".to_string());
+        }
+        BOOL.to_string()
+    }
+
+    fn visit_bitwise_not(&mut self,bitwise_not_expr:&Expr)->String{
+        let type_ = self.type_of(bitwise_not_expr);
+        if type_ != INT{
+            self.error(bitwise_not_expr.line(),format!("Argument of '~' has type {} instead of Int.",type_));
+        }
+        INT.to_string()
+    }
+
+    fn visit_not(&mut self,not_expr:&Expr)->String{
+        let type_ = self.type_of(not_expr);
+        if type_ != BOOL{
+            self.error(not_expr.line(),format!("Argument of 'not' has type {} instead of Bool.",type_));
+        }
+        BOOL.to_string()
+    }
+
+    fn visit_new(&mut self,new_expr:&Token)->String{
+        if !self.table.is_defined(&new_expr.lexeme){
+            self.error(new_expr.line,format!("'new' used with undefined class {}.",new_expr.lexeme));
+            return OBJECT.to_string();
+        }
+        new_expr.lexeme.clone()
+    }
+
+    fn visit_delete(&mut self,delete_expr:&Expr)->String{
+        self.type_of(delete_expr);
+        OBJECT.to_string()
+    }
+
+    fn visit_isvoid(&mut self,isvoid_expr:&Expr)->String{
+        self.type_of(isvoid_expr);
+        BOOL.to_string()
+    }
+
+    fn visit_grouping(&mut self,grouping_expr:&Expr)->String{
+        self.type_of(grouping_expr)
+    }
+
+    fn visit_block(&mut self,exprs:&Vec<Expr>)->String{
+        let mut type_ = OBJECT.to_string();
+        for expr in exprs{
+            type_ = self.type_of(expr);
+        }
+        type_
+    }
+
+    fn visit_if(&mut self,condition:&Expr,body:&Expr,else_expr:&Expr)->String{
+        if self.type_of(condition) != BOOL{
+            self.error(condition.line(),"Predicate of 'if' does not have type Bool.".to_string());
+        }
+        let then_type = self.type_of(body);
+        let else_type = self.type_of(else_expr);
+        self.lub(&then_type,&else_type)
+    }
+
+    fn visit_while(&mut self,condition:&Expr,body:&Expr)->String{
+        if self.type_of(condition) != BOOL{
+            self.error(condition.line(),"Loop condition does not have type Bool.".to_string());
+        }
+        self.type_of(body);
+        OBJECT.to_string()
+    }
+
+    fn visit_case(&mut self,condition:&Expr,branches:&Vec<Expr>)->String{
+        self.type_of(condition);
+        let mut seen : Vec<&str> = vec![];
+        let mut result : Option<String> = None;
+        for branch in branches{
+            if let Expr::Branch{type_,..} = branch{
+                if seen.contains(&type_.lexeme.as_str()){
+                    self.error(type_.line,format!("Duplicate branch {} in case statement.",type_.lexeme));
+                }
+                seen.push(&type_.lexeme);
+            }
+            let type_ = self.type_of(branch);
+            result = Some(match result{
+                Some(result) => self.lub(&result,&type_),
+                None => type_
+            });
+        }
+        result.unwrap_or(OBJECT.to_string())
+    }
+
+    fn visit_branch(&mut self,id:&Token,type_:&Token,expr:&Expr)->String{
+        let mut declared = type_.lexeme.clone();
+        if id.lexeme == "self"{
+            self.error(id.line,"'self' bound in 'case'.".to_string());
+        }
+        if declared == SELF_TYPE{
+            self.error(type_.line,format!("Identifier {} declared with type SELF_TYPE in case branch.",id.lexeme));
+            declared = OBJECT.to_string();
+        }
+        else if !self.table.is_defined(&declared){
+            self.error(type_.line,format!("Class {} of case branch is undefined.",declared));
+            declared = OBJECT.to_string();
+        }
+        self.scopes.push(HashMap::new());
+        if id.lexeme != "self"{
+            self.bind(&id.lexeme,&declared);
+        }
+        let type_ = self.type_of(expr);
+        self.scopes.pop();
+        type_
+    }
+
+    //each declaration is in scope for the ones after it and for the body
+    fn visit_let(&mut self,declarations:&Vec<Expr>,body:&Expr)->String{
+        let depth = self.scopes.len();
+        for declaration in declarations{
+            let declared = self.type_of(declaration);
+            if let Expr::Declaration{id,..} = declaration{
+                self.scopes.push(HashMap::new());
+                if id.lexeme != "self"{
+                    self.bind(&id.lexeme,&declared);
+                }
+            }
+        }
+        let type_ = self.type_of(body);
+        self.scopes.truncate(depth);
+        type_
+    }
+
+    fn visit_declaration(&mut self,id:&Token,type_:&Token,expr:&Option<Expr>)->String{
+        if id.lexeme == "self"{
+            self.error(id.line,"'self' cannot be bound in a 'let' expression.".to_string());
+        }
+        let mut declared = type_.lexeme.clone();
+        if !self.table.is_defined(&declared){
+            self.error(type_.line,format!("Class {} of let-bound identifier {} is undefined.",declared,id.lexeme));
+            declared = OBJECT.to_string();
+        }
+        if let Some(expr) = expr{
+            let inferred = self.type_of(expr);
+            if !self.conforms(&inferred,&declared){
+                self.error(id.line,format!("Inferred type {} of initialization of {} does not conform to identifier's declared type {}.",inferred,id.lexeme,declared));
+            }
+        }
+        declared
+    }
+
+    fn visit_stringliteral(&mut self,_stringliteral:&Token)->String{
+        STRING.to_string()
+    }
+
+    fn visit_integerliteral(&mut self,_integerliteral:&Token)->String{
+        INT.to_string()
+    }
+
+    fn visit_boolliteral(&mut self,_boolliteral:&Token)->String{
+        BOOL.to_string()
+    }
+
+    fn visit_id(&mut self,id:&Token)->String{
+        match self.lookup(&id.lexeme){
+            Some(type_) => type_,
+            None =>{
+                self.error(id.line,format!("Undeclared identifier {}.",id.lexeme));
+                OBJECT.to_string()
+            }
+        }
+    }
+
+    //`foo(x)` is parsed as a dispatch without a method name whose expression is the method id ,
+    //`e.foo(x)` and `e@T.foo(x)` carry the method name
+    fn visit_dispatch(&mut self,id:NodeId,target:&Option<Token>,expr:&Expr,method_name:&Option<Token>,arguments:&Vec<Expr>)->String{
+        let (receiver,method) = match (method_name,expr){
+            (Some(method),_) =>{
+                let receiver = self.type_of(expr);
+                self.types.insert(id,receiver.clone());
+                (receiver,method)
+            },
+            (None,Expr::ID{id}) => (SELF_TYPE.to_string(),id),
+            (None,_) => return OBJECT.to_string()
+        };
+        let argument_types : Vec<String> = arguments.iter().map(|a| self.type_of(a)).collect();
+
+        let mut class = self.resolve(&receiver).to_string();
+        if let Some(target) = target{
+            if target.lexeme == SELF_TYPE{
+                self.error(target.line,"Static dispatch to SELF_TYPE.".to_string());
+                return OBJECT.to_string();
+            }
+            if !self.table.is_defined(&target.lexeme){
+                self.error(target.line,format!("Static dispatch to undefined class {}.",target.lexeme));
+                return OBJECT.to_string();
+            }
+            if !self.conforms(&receiver,&target.lexeme){
+                self.error(target.line,format!("Expression type {} does not conform to declared static dispatch type {}.",receiver,target.lexeme));
+                return OBJECT.to_string();
+            }
+            class = target.lexeme.clone();
+        }
+
+        let signature = match self.table.find_method(&class,&method.lexeme){
+            Some(signature) => signature,
+            None =>{
+                let kind = if target.is_some() {"Static dispatch"} else {"Dispatch"};
+                self.error(method.line,format!("{} to undefined method {}.",kind,method.lexeme));
+                return OBJECT.to_string();
+            }
+        };
+        if signature.formals.len() != argument_types.len(){
+            self.error(method.line,format!("Method {} called with wrong number of arguments.",method.lexeme));
+        }
+        else{
+            for ((formal,formal_type),argument_type) in signature.formals.iter().zip(argument_types.iter()){
+                if !self.conforms(argument_type,formal_type){
+                    self.error(method.line,format!("In call of method {}, type {} of parameter {} does not conform to declared type {}.",method.lexeme,argument_type,formal,formal_type));
+                }
+            }
+        }
+        if signature.return_type == SELF_TYPE {receiver} else {signature.return_type.clone()}
+    }
+
+    fn not_implemented(&mut self)->String{
+        OBJECT.to_string()
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
 #[derive(Debug,Copy,Clone,PartialEq)]
 pub enum TokenType{
     //keywords
@@ -5,7 +8,7 @@ pub enum TokenType{
     KEYCASE,KEYESAC,KEYNEW,KEYOF,KEYTRUE,KEYFALSE,KEYDELETE,
     //All keywords are case-insensitive except true and false
 
-    INTEGER,IDENTIFIER,STRING,
+    INTEGER,TYPEID,OBJECTID,STRING,
 
     LEFTPAREN,RIGHTPAREN,LEFTBRACE,RIGHTBRACE,SEMICOLON,COLON,COMMA,
 
@@ -15,16 +18,142 @@ pub enum TokenType{
     EOF,
     ERROR
 }
+//region of the source a token or ast node covers . start and end are byte offsets , end is exclusive .
+//line and column (both starting at 1) are where the region starts
+#[derive(Debug,Copy,Clone,PartialEq,Eq,Default)]
+pub struct Span{
+    pub start : usize,
+    pub end : usize,
+    pub line : usize,
+    pub column : usize
+}
+impl Span{
+    pub fn new(start:usize,end:usize,line:usize,column:usize)->Span{
+        Span{start,end,line,column}
+    }
+
+    //smallest span covering both . the default span (from tokens made up during error recovery) is ignored
+    pub fn to(&self,other:Span)->Span{
+        if other == Span::default(){
+            return *self;
+        }
+        if *self == Span::default(){
+            return other;
+        }
+        let first = if self.start <= other.start {self} else {&other};
+        Span{start:first.start,end:self.end.max(other.end),line:first.line,column:first.column}
+    }
+
+    pub fn len(&self)->usize{
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.start == self.end
+    }
+}
+
+//value of a literal token , decoded and range checked once by the scanner so no later phase parses
+//the lexeme again . the literal of an ERROR token is the message the reference lexer gives for it
+#[derive(Debug,Clone,PartialEq)]
+pub enum Literal{
+    INT(i32),
+    STR(Arc<str>),
+    BOOL(bool)
+}
+
+//hands out one shared copy of every distinct string , equal string constants share their storage
+#[derive(Debug,Default)]
+pub struct Interner{
+    strings : HashSet<Arc<str>>
+}
+impl Interner{
+    pub fn new()->Interner{
+        Interner{strings:HashSet::new()}
+    }
+
+    pub fn intern(&mut self,value:&str)->Arc<str>{
+        if let Some(interned) = self.strings.get(value){
+            return interned.clone();
+        }
+        let interned : Arc<str> = Arc::from(value);
+        self.strings.insert(interned.clone());
+        interned
+    }
+}
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub enum TriviaKind{
+    WHITESPACE,
+    NEWLINE,
+    LINECOMMENT,
+    BLOCKCOMMENT
+}
+
+//source text between tokens , only kept when the scanner is asked to (Scanner::with_trivia)
+#[derive(Debug,Clone,PartialEq)]
+pub struct Trivia{
+    pub kind : TriviaKind,
+    pub text : String,
+    pub span : Span
+}
+
 #[derive(Debug,Clone)]
 pub struct Token{
    pub line : usize,
+   pub span : Span,
    pub lexeme : String,
   pub  tokentype : TokenType,
-   pub  literal : Option<String>
+   pub  literal : Option<Literal>,
+   //trivia before the token , starting at the line break that follows the previous token
+   pub leading : Vec<Trivia>,
+   //trivia after the token up to the end of its line , the line break itself leads the next token
+   pub trailing : Vec<Trivia>
 }
 impl Token{
-    pub fn new(line:usize,lexeme:String,tokentype:TokenType,literal:Option<String>)->Token{
-        Token { line, lexeme, tokentype ,literal  }
+    //line is where the token ends , which differs from span.line for strings spanning several lines
+    pub fn new(line:usize,span:Span,lexeme:String,tokentype:TokenType,literal:Option<Literal>)->Token{
+        Token { line, span, lexeme, tokentype ,literal ,leading:vec![],trailing:vec![] }
+    }
+
+    //the token as it was written , trivia included
+    pub fn full_text(&self)->String{
+        let mut text = String::new();
+        for trivia in self.leading.iter(){
+            text.push_str(&trivia.text);
+        }
+        if self.tokentype != TokenType::EOF{
+            text.push_str(&self.lexeme);
+        }
+        for trivia in self.trailing.iter(){
+            text.push_str(&trivia.text);
+        }
+        text
+    }
+
+    //the original source , given every token of a scanner that kept its trivia
+    pub fn source_text(tokens:&[Token])->String{
+        tokens.iter().map(|t| t.full_text()).collect()
+    }
+
+    //value of an INTEGER token
+    pub fn int_value(&self)->i32{
+        match self.literal{
+            Some(Literal::INT(value)) => value,
+            _ => 0
+        }
+    }
+
+    //value of a STRING token with the escapes decoded , or the message of an ERROR token
+    pub fn string_value(&self)->&str{
+        match &self.literal{
+            Some(Literal::STR(value)) => value,
+            _ => ""
+        }
+    }
+
+    pub fn bool_value(&self)->bool{
+        matches!(self.literal,Some(Literal::BOOL(true)))
     }
     
     pub fn keyword(lexeme:&str)->Option<TokenType>{
@@ -0,0 +1,111 @@
+use crate::token::*;
+use crate::token::TokenType::*;
+
+/*
+prints tokens the way the reference flex lexer does , so the output can be diffed line for line
+
+#name "examples/hello_world.cl"
+#1 CLASS
+#1 TYPEID Main
+#2 OBJECTID out_string
+#2 '('
+#2 STR_CONST "Hello, World.\n"
+#2 ERROR "EOF in comment"
+
+>, >= and delete are extensions of this compiler , they are printed as '>' , GE and DELETE.
+*/
+
+pub struct TokenPrinter;
+
+impl TokenPrinter{
+    pub fn print_tokens(filename:&str,tokens:&[Token])->String{
+        let mut output = format!("#name \"{}\"\n",filename);
+        for token in tokens.iter().filter(|t| t.tokentype != EOF){
+            output.push_str(&Self::print_token(token));
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn print_token(token:&Token)->String{
+        let name = Self::token_name(token);
+        match token.tokentype{
+            STRING => format!("#{} {} \"{}\"",token.line,name,escape(token.string_value())),
+            ERROR => format!("#{} {} \"{}\"",token.line,name,escape(token.string_value())),
+            INTEGER|TYPEID|OBJECTID => format!("#{} {} {}",token.line,name,token.lexeme),
+            KEYTRUE|KEYFALSE => format!("#{} {} {}",token.line,name,token.bool_value()),
+            _ => format!("#{} {}",token.line,name)
+        }
+    }
+
+    //the name the reference lexer uses for the token
+    pub fn token_name(token:&Token)->&'static str{
+        match token.tokentype{
+            KEYCLASS => "CLASS",
+            KEYIF => "IF",
+            KEYELSE => "ELSE",
+            KEYTHEN => "THEN",
+            KEYFI => "FI",
+            KEYIN => "IN",
+            KEYINHERITS => "INHERITS",
+            KEYLET => "LET",
+            KEYLOOP => "LOOP",
+            KEYPOOL => "POOL",
+            KEYWHILE => "WHILE",
+            KEYCASE => "CASE",
+            KEYESAC => "ESAC",
+            KEYNEW => "NEW",
+            KEYOF => "OF",
+            KEYDELETE => "DELETE",
+            KEYTRUE|KEYFALSE => "BOOL_CONST",
+            ISVOID => "ISVOID",
+            NOT => "NOT",
+            INTEGER => "INT_CONST",
+            STRING => "STR_CONST",
+            TYPEID => "TYPEID",
+            OBJECTID => "OBJECTID",
+            LEFTPAREN => "'('",
+            RIGHTPAREN => "')'",
+            LEFTBRACE => "'{'",
+            RIGHTBRACE => "'}'",
+            SEMICOLON => "';'",
+            COLON => "':'",
+            COMMA => "','",
+            DOT => "'.'",
+            AT => "'@'",
+            TILDA => "'~'",
+            STAR => "'*'",
+            SLASH => "'/'",
+            PLUS => "'+'",
+            MINUS => "'-'",
+            LESSTHAN => "'<'",
+            MORETHAN => "'>'",
+            EQUALITY => "'='",
+            ASSIGN => "ASSIGN",
+            LESSEQUAL => "LE",
+            MOREEQUAL => "GE",
+            RARROW => "DARROW",
+            EOF => "EOF",
+            ERROR => "ERROR"
+        }
+    }
+}
+
+//escapes a string the way the reference compiler prints string constants . bytes that are not
+//printable come out as a backslash and three octal digits
+pub fn escape(value:&str)->String{
+    let mut escaped = String::new();
+    for byte in value.bytes(){
+        match byte{
+            b'\\' => escaped.push_str("\\\\"),
+            b'"' => escaped.push_str("\\\""),
+            b'\n' => escaped.push_str("\\n"),
+            b'\t' => escaped.push_str("\\t"),
+            0x08 => escaped.push_str("\\b"),
+            0x0c => escaped.push_str("\\f"),
+            0x20..=0x7e => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\{:03o}",byte))
+        }
+    }
+    escaped
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use std::process::{Command,Stdio};
+use cool_compiler_rs::{asm,semant};
+use cool_compiler_rs::interpreter::Interpreter;
+use common::{parse,read};
+
+/*
+the mips code of the examples run under spim , what they print has to match the interpreter.
+the test is skipped when spim is not installed .
+the files in tests/data/asm only catch unintended changes to the code generator , they do not show the
+code is right . after a deliberate change they are written again with
+cool-compiler-rs -o tests/data/asm/hello_world.s examples/hello_world.cl and cool-compiler-rs -g -o tests/data/asm/list.gc.s examples/list.cl
+*/
+
+//examples that stop on their own and read no input
+const RUNNABLE:[&str;7] = ["hello_world","list","book_list","cells","complex","hairyscary","lam"];
+
+fn assembly(name:&str,gc:bool)->String{
+    let program = parse(&format!("examples/{}.cl",name));
+    let Ok(analysis) = semant::check(&program) else{
+        panic!("examples/{}.cl has errors",name);
+    };
+    asm::generate(&program,&analysis,gc)
+}
+
+fn interpret(name:&str)->String{
+    let program = parse(&format!("examples/{}.cl",name));
+    let analysis = semant::check(&program).unwrap();
+    let mut output = vec![];
+    Interpreter::new(&program,&analysis.table,Box::new(std::io::empty()),Box::new(&mut output)).run().unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+//what the program prints under spim , without the banner of spim and the line of the runtime at the end .
+//None when spim is not installed
+fn spim(name:&str,code:&str)->Option<String>{
+    let path = format!("{}/spim_{}.s",env!("CARGO_TARGET_TMPDIR"),name);
+    std::fs::write(&path,code).unwrap();
+    let output = match Command::new("spim").args(["-file",&path]).stdin(Stdio::null()).output(){
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => panic!("spim: {}",e)
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let start = stdout.find("trap.handler\n").map_or(0,|at| at + "trap.handler\n".len());
+    let end = stdout.rfind("COOL program successfully executed").unwrap_or_else(|| panic!("{}: {}",name,stdout));
+    Some(stdout[start..end].to_string())
+}
+
+#[test]
+fn examples_run_under_spim_like_the_interpreter(){
+    for name in RUNNABLE{
+        for gc in [false,true]{
+            let Some(output) = spim(name,&assembly(name,gc)) else{
+                eprintln!("spim is not installed , skipped");
+                return;
+            };
+            //the runtime prints a newline before its last line
+            assert_eq!(output.strip_suffix('\n').unwrap_or(&output),interpret(name),"{} gc {}",name,gc);
+        }
+    }
+}
+
+#[test]
+fn assembly_is_unchanged(){
+    assert!(assembly("hello_world",false) == read("tests/data/asm/hello_world.s"));
+    assert!(assembly("list",true) == read("tests/data/asm/list.gc.s"));
+}
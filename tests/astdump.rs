@@ -0,0 +1,61 @@
+mod common;
+
+use cool_compiler_rs::astdump::AstDump;
+use common::parse;
+
+#[test]
+fn hello_world(){
+    assert_eq!(AstDump::dump_program(&parse("examples/hello_world.cl")),"\
+#1
+_program
+  #1
+  _class
+    Main
+    IO
+    \"examples/hello_world.cl\"
+    (
+    #2
+    _method
+      main
+      SELF_TYPE
+      #3
+      _dispatch
+        #3
+        _object
+          self
+        : _no_type
+        out_string
+        (
+        #3
+        _string
+          \"Hello, World.\\n\"
+        : _no_type
+        )
+      : _no_type
+    )
+");
+}
+
+//every kind of node , compared with the dump kept next to the source
+#[test]
+fn every_node_kind(){
+    assert_eq!(AstDump::dump_program(&parse("tests/data/dump.cl")),common::read("tests/data/dump.ast"));
+}
+
+//a #line before every node , two more spaces for every level
+#[test]
+fn examples_are_well_formed(){
+    for (name,_) in common::examples(){
+        let dump = AstDump::dump_program(&parse(&name));
+        let lines : Vec<&str> = dump.lines().collect();
+        for (i,line) in lines.iter().enumerate(){
+            let indent = line.len() - line.trim_start().len();
+            assert_eq!(indent % 2,0,"{}:{}",name,i + 1);
+            if line.trim_start().starts_with('#'){
+                assert!(line.trim_start()[1..].parse::<usize>().is_ok_and(|n| n > 0),"{}:{}",name,i + 1);
+                let next = lines[i + 1];
+                assert!(next.starts_with(&format!("{}_",&line[..indent])),"{}:{}",name,i + 2);
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use cool_compiler_rs::astexport;
+use common::{parse,read};
+
+fn examples()->Vec<String>{
+    let mut names = vec!["tests/data/export.cl".to_string()];
+    names.extend(common::examples().into_iter().map(|(name,_)| name));
+    names
+}
+
+//the number after a "name": , or after the name in a (span ...)
+fn number(text:&str)->(usize,&str){
+    let end = text.find(|c:char| !c.is_ascii_digit()).unwrap();
+    (text[..end].parse().unwrap(),&text[end..])
+}
+
+//lexeme , start and end of every token of the json , in order
+fn json_tokens(json:&str)->Vec<(String,usize,usize)>{
+    let mut tokens = vec![];
+    for piece in json.split("{\"lexeme\":\"").skip(1){
+        let (lexeme,rest) = piece.split_once("\",\"span\":{\"start\":").unwrap();
+        let (start,rest) = number(rest);
+        let (end,_) = number(rest.strip_prefix(",\"end\":").unwrap());
+        tokens.push((lexeme.to_string(),start,end));
+    }
+    tokens
+}
+
+fn sexp_tokens(sexp:&str)->Vec<(String,usize,usize)>{
+    let mut tokens = vec![];
+    for piece in sexp.split("(token \"").skip(1){
+        let (lexeme,rest) = piece.split_once("\" (span ").unwrap();
+        let (start,rest) = number(rest);
+        let (end,_) = number(&rest[1..]);
+        tokens.push((lexeme.to_string(),start,end));
+    }
+    tokens
+}
+
+#[test]
+fn every_node_kind_as_json(){
+    assert_eq!(astexport::json(&parse("tests/data/export.cl")),read("tests/data/export.json"));
+}
+
+#[test]
+fn every_node_kind_as_an_s_expression(){
+    assert_eq!(astexport::sexp(&parse("tests/data/export.cl")),read("tests/data/export.sexp"));
+}
+
+#[test]
+fn hello_world(){
+    let sexp = astexport::sexp(&parse("examples/hello_world.cl"));
+    assert!(sexp.starts_with("(program\n  (version 1)\n  (classes (\n    (class\n      (span 0 86 1 1)\n"),"{}",sexp);
+    assert!(sexp.contains("(receiver nil)\n"));
+    assert!(sexp.contains("(lexeme \"\\\"Hello, World.\\\\n\\\"\")\n"));
+    assert!(sexp.contains("(value \"Hello, World.\\n\")"));
+    let json = astexport::json(&parse("examples/hello_world.cl"));
+    assert!(json.starts_with("{\n  \"kind\":\"program\",\n  \"version\":1,\n  \"classes\":[\n"),"{}",json);
+    assert!(json.contains("\"method\":{\"lexeme\":\"out_string\",\"span\":{\"start\":49,\"end\":59,\"line\":3,\"column\":2}}"));
+}
+
+//every name , type and operator points at its lexeme in the source
+#[test]
+fn token_spans_cover_their_lexemes(){
+    for name in examples(){
+        let source = read(&name);
+        let program = parse(&name);
+        let tokens = json_tokens(&astexport::json(&program));
+        assert!(!tokens.is_empty(),"{}",name);
+        for (lexeme,start,end) in tokens.iter(){
+            assert_eq!(&source[*start..*end],lexeme,"{}",name);
+        }
+        assert_eq!(sexp_tokens(&astexport::sexp(&program)),tokens,"{}",name);
+    }
+}
+
+#[test]
+fn s_expressions_are_balanced(){
+    for name in examples(){
+        let sexp = astexport::sexp(&parse(&name));
+        let (mut depth,mut in_string,mut escaped) = (0i64,false,false);
+        for c in sexp.chars(){
+            match c{
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                '(' if !in_string => depth += 1,
+                ')' if !in_string =>{
+                    depth -= 1;
+                    assert!(depth >= 0,"{}",name);
+                },
+                _ => ()
+            }
+        }
+        assert_eq!((depth,in_string),(0,false),"{}",name);
+    }
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use cool_compiler_rs::ast::{Expr,Feature};
+use cool_compiler_rs::astdump::AstDump;
+use cool_compiler_rs::astreader::AstReader;
+use cool_compiler_rs::{semant,asm};
+use common::parse;
+
+fn examples()->Vec<String>{
+    let mut names = vec!["tests/data/dump.cl".to_string()];
+    names.extend(common::examples().into_iter().map(|(name,_)| name));
+    names
+}
+
+//the message and the line of the dump of the first problem
+fn error(dump:&str)->(String,usize){
+    let diagnostic = AstReader::read_program(dump).unwrap_err();
+    assert_eq!(diagnostic.code,"E0200");
+    (diagnostic.message,diagnostic.span.line)
+}
+
+#[test]
+fn examples_read_back_to_the_same_dump(){
+    for name in examples(){
+        let dump = AstDump::dump_program(&parse(&name));
+        let program = AstReader::read_program(&dump).unwrap_or_else(|d| panic!("{}: {}",name,d));
+        assert_eq!(AstDump::dump_program(&program),dump,"{}",name);
+    }
+}
+
+#[test]
+fn every_node_kind(){
+    let dump = common::read("tests/data/dump.ast");
+    assert_eq!(AstDump::dump_program(&AstReader::read_program(&dump).unwrap()),dump);
+}
+
+#[test]
+fn hello_world(){
+    let dump = AstDump::dump_program(&parse("examples/hello_world.cl"));
+    let program = AstReader::read_program(&dump).unwrap();
+    let class = &program.classes()[0];
+    assert_eq!(class.type_().lexeme,"Main");
+    assert_eq!(class.inherits().as_ref().unwrap().lexeme,"IO");
+    assert_eq!(class.filename(),"examples/hello_world.cl");
+    let Feature::Method{id,type_,body,..} = &class.features()[0] else{
+        panic!("main is not a method");
+    };
+    assert_eq!((id.lexeme.as_str(),id.line,type_.lexeme.as_str()),("main",2,"SELF_TYPE"));
+    //out_string(...) reads back as self.out_string(...)
+    let Expr::Dispatch{target:None,expr,method_name:Some(method),arguments,..} = body else{
+        panic!("the body is not a dispatch");
+    };
+    assert!(matches!(expr.as_ref(),Expr::ID{id} if id.lexeme == "self" && id.line == 3));
+    assert_eq!(method.lexeme,"out_string");
+    let [Expr::StringLiteral{value}] = arguments.as_slice() else{
+        panic!("the argument is not a string");
+    };
+    assert_eq!((value.string_value(),value.lexeme.as_str(),value.line),("Hello, World.\n","\"Hello, World.\\n\"",3));
+}
+
+//the dump of the type checker (coolc -s) has the types in place of _no_type
+#[test]
+fn types_are_ignored(){
+    let dump = AstDump::dump_program(&parse("tests/data/dump.cl"));
+    let typed = dump.replace(": _no_type",": Int");
+    assert_eq!(AstDump::dump_program(&AstReader::read_program(&typed).unwrap()),dump);
+}
+
+//a runtime error in a dispatch reports the line of its method name , the dump only keeps the line of
+//the dispatch , so the line loaded before the jump to the abort routine may differ
+fn without_abort_lines(code:&str)->String{
+    let lines : Vec<&str> = code.lines().collect();
+    let mut result = String::new();
+    for (i,line) in lines.iter().enumerate(){
+        let aborts = lines.get(i + 1).is_some_and(|next| next.contains("_abort"));
+        if !(aborts && line.starts_with("\tli\t$t1 ")){
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    result
+}
+
+#[test]
+fn compiles_to_the_same_code_as_the_source(){
+    let mut compiled = 0;
+    for name in examples(){
+        let program = parse(&name);
+        let Ok(analysis) = semant::check(&program) else{
+            continue;
+        };
+        let read = AstReader::read_program(&AstDump::dump_program(&program)).unwrap();
+        let read_analysis = semant::check(&read).unwrap_or_else(|_| panic!("{}",name));
+        assert_eq!(without_abort_lines(&asm::generate(&read,&read_analysis,false)),
+            without_abort_lines(&asm::generate(&program,&analysis,false)),"{}",name);
+        compiled += 1;
+    }
+    assert!(compiled > 10);
+}
+
+#[test]
+fn semantic_errors_keep_their_lines(){
+    let dump = "#1\n_program\n  #1\n  _class\n    Main\n    Object\n    \"main.cl\"\n    (\n    #4\n    _method\n      main\n      Int\n      #5\n      _bool\n        1\n      : _no_type\n    )\n";
+    let Err(errors) = semant::check(&AstReader::read_program(dump).unwrap()) else{
+        panic!("main returns a Bool");
+    };
+    let reports : Vec<String> = errors.iter().map(|e| e.report()).collect();
+    assert!(reports.iter().any(|r| r.starts_with("main.cl:5:")),"{:?}",reports);
+}
+
+#[test]
+fn rejects_malformed_dumps(){
+    let class = "#1\n_program\n  #1\n  _class\n    Main\n    Object\n    \"a.cl\"\n    (\n";
+    let method = format!("{}    #2\n    _method\n      main\n      Int\n",class);
+    assert_eq!(error(""),("expected a line number like #1 , found the end of the dump".to_string(),1));
+    assert_eq!(error("#1\n_program\n"),("a program needs at least one class".to_string(),2));
+    assert_eq!(error("1\n_program\n"),("expected a line number like #1 , found '1'".to_string(),1));
+    assert_eq!(error("#1\n_programme\n"),("expected '_program' , found '_programme'".to_string(),2));
+    assert_eq!(error(class),("expected ')' , found the end of the dump".to_string(),8));
+    assert_eq!(error(&class.replace("    Main","    main")),("expected a type name , found 'main'".to_string(),5));
+    assert_eq!(error(&class.replace("\"a.cl\"","a.cl")),("expected a quoted string , found 'a.cl'".to_string(),7));
+    assert_eq!(error(&class.replace("    (","   (")),("expected '(' indented by 4 , found '(' indented by 3".to_string(),8));
+    assert_eq!(error(&format!("{}    #2\n    _feature\n",class)),("expected '_method' or '_attr' , found '_feature'".to_string(),10));
+    let body = |expr:&str| format!("{}      #3\n{}\n      : _no_type\n    )\n",method,expr);
+    assert_eq!(error(&body("      _no_expr")),("'_no_expr' where an expression is required".to_string(),14));
+    assert_eq!(error(&body("      _foo")),("unknown node '_foo'".to_string(),14));
+    assert_eq!(error(&body("      _int\n        2147483648")),("expected an integer from 0 to 2147483647 , found '2147483648'".to_string(),15));
+    assert_eq!(error(&body("      _int\n        -1")).0,"expected an integer from 0 to 2147483647 , found '-1'");
+    assert_eq!(error(&body("      _bool\n        true")).0,"expected 1 or 0 , found 'true'");
+    assert_eq!(error(&body("      _string\n        \"a\\qb\"")).0,"unknown escape in string \"a\\qb\"");
+    assert_eq!(error(&body("      _string\n        \"\\377\"")).0,"invalid utf-8 in string \"\\377\"");
+    assert_eq!(error(&body("      _block")),("a block needs at least one expression".to_string(),14));
+    assert_eq!(error(&body("      _object\n        x\n      : Int Int")).0,"expected the type of the expression like ': _no_type' , found ': Int Int'");
+    assert_eq!(error(&format!("{}      #3\n      _object\n        x\n    )\n",method)),
+        ("expected the type of the expression like ': _no_type' indented by 6 , found ')' indented by 4".to_string(),16));
+}
+
+#[test]
+fn reads_escapes_like_the_reference_prints_them(){
+    let dump = "#1\n_program\n  #1\n  _class\n    Main\n    Object\n    \"a.cl\"\n    (\n    #2\n    _attr\n      s\n      String\n      #2\n      _string\n        \"tab\\tquote\\\"back\\\\slash\\033caf\\303\\251\"\n      : _no_type\n    )\n";
+    let program = AstReader::read_program(dump).unwrap();
+    let Some(Expr::StringLiteral{value}) = program.classes()[0].features()[0].init() else{
+        panic!("s has no string");
+    };
+    assert_eq!(value.string_value(),"tab\tquote\"back\\slash\u{1b}café");
+    assert_eq!(AstDump::dump_program(&program),dump);
+}
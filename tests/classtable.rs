@@ -0,0 +1,79 @@
+mod common;
+
+use common::semant_errors;
+
+/*
+the checks of the inheritance graph , with the messages of the reference coolc .
+every program has a Main so only the error under test is reported
+*/
+
+const MAIN:&str = "class Main { main() : Int { 0 }; };\n";
+
+fn errors(classes:&str)->Vec<String>{
+    semant_errors(&format!("{}{}",classes,MAIN))
+}
+
+#[test]
+fn a_well_formed_graph_has_no_errors(){
+    assert_eq!(errors("class B inherits A {};\nclass A inherits IO {};\n"),Vec::<String>::new());
+}
+
+#[test]
+fn inheritance_cycles(){
+    assert_eq!(errors("class A inherits B {};\nclass B inherits C {};\nclass C inherits A {};\n"),vec![
+        "test.cl:1: Class A, or an ancestor of A, is involved in an inheritance cycle.",
+        "test.cl:2: Class B, or an ancestor of B, is involved in an inheritance cycle.",
+        "test.cl:3: Class C, or an ancestor of C, is involved in an inheritance cycle."
+    ]);
+    assert_eq!(errors("class A inherits A {};\n"),vec![
+        "test.cl:1: Class A, or an ancestor of A, is involved in an inheritance cycle."
+    ]);
+    //below a cycle but not in it
+    assert_eq!(errors("class A inherits B {};\nclass B inherits A {};\nclass C inherits A {};\n").len(),3);
+}
+
+#[test]
+fn undefined_parents(){
+    assert_eq!(errors("class A inherits Nope {};\n"),vec![
+        "test.cl:1: Class A inherits from an undefined class Nope."
+    ]);
+}
+
+#[test]
+fn basic_classes_cannot_be_inherited(){
+    //inherits SELF_TYPE is already a syntax error
+    for basic in ["Int","String","Bool"]{
+        assert_eq!(errors(&format!("class A inherits {} {{}};\n",basic)),vec![
+            format!("test.cl:1: Class A cannot inherit class {}.",basic)
+        ]);
+    }
+    assert_eq!(errors("class A inherits IO {};\nclass B inherits Object {};\n"),Vec::<String>::new());
+}
+
+#[test]
+fn basic_classes_cannot_be_redefined(){
+    //class Object and class SELF_TYPE are already syntax errors
+    for basic in ["IO","Int","String","Bool"]{
+        assert_eq!(errors(&format!("\nclass {} {{}};\n",basic)),vec![
+            format!("test.cl:2: Redefinition of basic class {}.",basic)
+        ]);
+    }
+}
+
+#[test]
+fn classes_are_defined_once(){
+    assert_eq!(errors("class A {};\nclass A inherits IO {};\n"),vec![
+        "test.cl:2: Class A was previously defined."
+    ]);
+}
+
+#[test]
+fn main_class_and_method(){
+    //a program without Main is reported without a line , like coolc does
+    assert_eq!(semant_errors("class A {};\n"),vec!["Class Main is not defined."]);
+    assert_eq!(semant_errors("class A {};\nclass Main { x : Int; };\n"),vec![
+        "test.cl:2: No 'main' method in class Main."
+    ]);
+    //an inherited main is enough
+    assert_eq!(semant_errors("class A { main() : Int { 0 }; };\nclass Main inherits A {};\n"),Vec::<String>::new());
+}
@@ -0,0 +1,60 @@
+#![allow(dead_code)]
+
+use cool_compiler_rs::{Scanner,Parser};
+use cool_compiler_rs::ast::Program;
+
+/*
+helpers shared by the integration tests , every test crate takes what it needs with mod common.
+paths are relative to the root of the crate , the way the driver prints them
+*/
+
+pub fn path(relative:&str)->String{
+    format!("{}/{}",env!("CARGO_MANIFEST_DIR"),relative)
+}
+
+pub fn read(relative:&str)->String{
+    std::fs::read_to_string(path(relative)).unwrap_or_else(|e| panic!("{}: {}",relative,e))
+}
+
+//every .cl file in the directories , as (path , source) , sorted by path
+pub fn sources(dirs:&[&str])->Vec<(String,String)>{
+    let mut names = vec![];
+    for dir in dirs{
+        for entry in std::fs::read_dir(path(dir)).unwrap(){
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            if name.ends_with(".cl"){
+                names.push(format!("{}/{}",dir,name));
+            }
+        }
+    }
+    names.sort();
+    assert!(!names.is_empty());
+    names.into_iter().map(|name|{
+        let source = read(&name);
+        (name,source)
+    }).collect()
+}
+
+//the example programs shipped with the compiler
+pub fn examples()->Vec<(String,String)>{
+    sources(&["examples"])
+}
+
+//scans and parses a file that has no errors , its classes remember the path
+pub fn parse(relative:&str)->Program{
+    let tokens = Scanner::new(read(relative)).scan_tokens().unwrap();
+    let mut program = Parser::new(tokens).parse_program().unwrap();
+    program.set_filename(relative);
+    program
+}
+
+//the errors of semantic analysis as the driver prints them , test.cl:line: message
+pub fn semant_errors(source:&str)->Vec<String>{
+    let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+    let mut program = Parser::new(tokens).parse_program().unwrap();
+    program.set_filename("test.cl");
+    match cool_compiler_rs::semant::check(&program){
+        Ok(_) => vec![],
+        Err(errors) => errors.iter().map(|e| e.report()).collect()
+    }
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use cool_compiler_rs::{Scanner,Parser};
+use cool_compiler_rs::cst::{SyntaxKind,SyntaxNode};
+use cool_compiler_rs::lower::lower;
+
+fn sources()->Vec<(String,String)>{
+    common::sources(&["examples","tests/data"])
+}
+
+fn cst(source:&str)->(SyntaxNode,usize){
+    let mut scanner = Scanner::new(source.to_string()).with_trivia();
+    let (root,diagnostics) = Parser::new(&mut scanner).parse_cst();
+    (root,diagnostics.len() + scanner.take_diagnostics().len())
+}
+
+fn kinds(node:&SyntaxNode)->Vec<SyntaxKind>{
+    node.children().iter().map(|n| n.kind()).collect()
+}
+
+#[test]
+fn round_trips_every_example(){
+    for (path,source) in sources(){
+        let (root,_) = cst(&source);
+        assert_eq!(root.text(),source,"{}",path);
+        assert_eq!(root.text_range(),0..source.len());
+    }
+}
+
+#[test]
+fn lowers_to_the_parsed_ast(){
+    for (path,source) in sources(){
+        let (root,errors) = cst(&source);
+        let Ok(tokens) = Scanner::new(source.clone()).scan_tokens() else {continue};
+        let Ok(parsed) = Parser::new(tokens).parse_program() else {continue};
+        assert_eq!(errors,0,"{}",path);
+        let lowered = lower(&root).unwrap();
+        assert_eq!(format!("{:?}",lowered.classes()),format!("{:?}",parsed.classes()),"{}",path);
+    }
+}
+
+#[test]
+fn keeps_broken_source(){
+    let source = "class A {\n  f() : Int { { 1 + ; 2; } };\n  x : Int <- # 3;\n};\nclass B inherits { };\n(* trailing";
+    let (root,errors) = cst(source);
+    assert!(errors > 0);
+    assert_eq!(root.text(),source);
+    assert!(lower(&root).is_none());
+}
+
+#[test]
+fn node_shapes(){
+    let source = "class Main inherits IO {\n  -- entry point\n  main() : Object { out_string(\"hi\").length() };\n  x : Int <- 1 + 2 * 3;\n};\n";
+    let (root,errors) = cst(source);
+    assert_eq!(errors,0);
+    assert_eq!(root.kind(),SyntaxKind::PROGRAM);
+    let class = &root.children()[0];
+    assert_eq!(kinds(class),vec![SyntaxKind::METHOD,SyntaxKind::ATTRIBUTE]);
+
+    let method = &class.children()[0];
+    assert_eq!(method.tokens()[0].leading()[2].text,"-- entry point");
+    assert_eq!(&source[method.trimmed_range()],"main() : Object { out_string(\"hi\").length() }");
+    let call = &method.children()[0];
+    assert_eq!(call.kind(),SyntaxKind::DISPATCH_EXPR);
+    assert_eq!(kinds(call),vec![SyntaxKind::DISPATCH_EXPR,SyntaxKind::ARG_LIST]);
+    assert_eq!(call.parent().unwrap().kind(),SyntaxKind::METHOD);
+
+    let sum = &class.children()[1].children()[0];
+    assert_eq!(kinds(sum),vec![SyntaxKind::LITERAL,SyntaxKind::BINARY_EXPR]);
+    assert_eq!(&source[sum.children()[1].trimmed_range()],"2 * 3");
+}
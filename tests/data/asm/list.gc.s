@@ -0,0 +1,794 @@
+	.data
+	.align	2
+	.globl	class_nameTab
+	.globl	Main_protObj
+	.globl	Int_protObj
+	.globl	String_protObj
+	.globl	bool_const0
+	.globl	bool_const1
+	.globl	_int_tag
+	.globl	_bool_tag
+	.globl	_string_tag
+_int_tag:
+	.word	3
+_bool_tag:
+	.word	5
+_string_tag:
+	.word	4
+	.globl	_MemMgr_INITIALIZER
+_MemMgr_INITIALIZER:
+	.word	_GenGC_Init
+	.globl	_MemMgr_COLLECTOR
+_MemMgr_COLLECTOR:
+	.word	_GenGC_Collect
+	.globl	_MemMgr_TEST
+_MemMgr_TEST:
+	.word	0
+	.word	-1
+str_const0:
+	.word	4
+	.word	6
+	.word	String_dispTab
+	.word	int_const6
+	.ascii	"Object"
+	.byte	0
+	.align	2
+	.word	-1
+str_const1:
+	.word	4
+	.word	5
+	.word	String_dispTab
+	.word	int_const4
+	.ascii	"IO"
+	.byte	0
+	.align	2
+	.word	-1
+str_const2:
+	.word	4
+	.word	6
+	.word	String_dispTab
+	.word	int_const2
+	.ascii	"Main"
+	.byte	0
+	.align	2
+	.word	-1
+str_const3:
+	.word	4
+	.word	5
+	.word	String_dispTab
+	.word	int_const3
+	.ascii	"Int"
+	.byte	0
+	.align	2
+	.word	-1
+str_const4:
+	.word	4
+	.word	6
+	.word	String_dispTab
+	.word	int_const6
+	.ascii	"String"
+	.byte	0
+	.align	2
+	.word	-1
+str_const5:
+	.word	4
+	.word	6
+	.word	String_dispTab
+	.word	int_const2
+	.ascii	"Bool"
+	.byte	0
+	.align	2
+	.word	-1
+str_const6:
+	.word	4
+	.word	6
+	.word	String_dispTab
+	.word	int_const2
+	.ascii	"List"
+	.byte	0
+	.align	2
+	.word	-1
+str_const7:
+	.word	4
+	.word	6
+	.word	String_dispTab
+	.word	int_const2
+	.ascii	"Cons"
+	.byte	0
+	.align	2
+	.word	-1
+str_const8:
+	.word	4
+	.word	9
+	.word	String_dispTab
+	.word	int_const7
+	.ascii	"examples/list.cl"
+	.byte	0
+	.align	2
+	.word	-1
+str_const9:
+	.word	4
+	.word	5
+	.word	String_dispTab
+	.word	int_const0
+	.ascii	""
+	.byte	0
+	.align	2
+	.word	-1
+str_const10:
+	.word	4
+	.word	5
+	.word	String_dispTab
+	.word	int_const5
+	.byte	10
+	.byte	0
+	.align	2
+	.word	-1
+str_const11:
+	.word	4
+	.word	5
+	.word	String_dispTab
+	.word	int_const5
+	.ascii	" "
+	.byte	0
+	.align	2
+	.word	-1
+int_const0:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	0
+	.word	-1
+int_const1:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	5
+	.word	-1
+int_const2:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	4
+	.word	-1
+int_const3:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	3
+	.word	-1
+int_const4:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	2
+	.word	-1
+int_const5:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	1
+	.word	-1
+int_const6:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	6
+	.word	-1
+int_const7:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	16
+	.word	-1
+bool_const0:
+	.word	5
+	.word	4
+	.word	Bool_dispTab
+	.word	0
+	.word	-1
+bool_const1:
+	.word	5
+	.word	4
+	.word	Bool_dispTab
+	.word	1
+class_nameTab:
+	.word	str_const0
+	.word	str_const1
+	.word	str_const2
+	.word	str_const3
+	.word	str_const4
+	.word	str_const5
+	.word	str_const6
+	.word	str_const7
+class_objTab:
+	.word	Object_protObj
+	.word	Object_init
+	.word	IO_protObj
+	.word	IO_init
+	.word	Main_protObj
+	.word	Main_init
+	.word	Int_protObj
+	.word	Int_init
+	.word	String_protObj
+	.word	String_init
+	.word	Bool_protObj
+	.word	Bool_init
+	.word	List_protObj
+	.word	List_init
+	.word	Cons_protObj
+	.word	Cons_init
+Object_dispTab:
+	.word	Object.abort
+	.word	Object.type_name
+	.word	Object.copy
+IO_dispTab:
+	.word	Object.abort
+	.word	Object.type_name
+	.word	Object.copy
+	.word	IO.out_string
+	.word	IO.out_int
+	.word	IO.in_string
+	.word	IO.in_int
+Main_dispTab:
+	.word	Object.abort
+	.word	Object.type_name
+	.word	Object.copy
+	.word	IO.out_string
+	.word	IO.out_int
+	.word	IO.in_string
+	.word	IO.in_int
+	.word	Main.print_list
+	.word	Main.main
+Int_dispTab:
+	.word	Object.abort
+	.word	Object.type_name
+	.word	Object.copy
+String_dispTab:
+	.word	Object.abort
+	.word	Object.type_name
+	.word	Object.copy
+	.word	String.length
+	.word	String.concat
+	.word	String.substr
+Bool_dispTab:
+	.word	Object.abort
+	.word	Object.type_name
+	.word	Object.copy
+List_dispTab:
+	.word	Object.abort
+	.word	Object.type_name
+	.word	Object.copy
+	.word	List.isNil
+	.word	List.head
+	.word	List.tail
+	.word	List.cons
+Cons_dispTab:
+	.word	Object.abort
+	.word	Object.type_name
+	.word	Object.copy
+	.word	Cons.isNil
+	.word	Cons.head
+	.word	Cons.tail
+	.word	List.cons
+	.word	Cons.init
+	.word	-1
+Object_protObj:
+	.word	0
+	.word	3
+	.word	Object_dispTab
+	.word	-1
+IO_protObj:
+	.word	1
+	.word	3
+	.word	IO_dispTab
+	.word	-1
+Main_protObj:
+	.word	2
+	.word	4
+	.word	Main_dispTab
+	.word	0
+	.word	-1
+Int_protObj:
+	.word	3
+	.word	4
+	.word	Int_dispTab
+	.word	0
+	.word	-1
+String_protObj:
+	.word	4
+	.word	5
+	.word	String_dispTab
+	.word	int_const0
+	.word	0
+	.word	-1
+Bool_protObj:
+	.word	5
+	.word	4
+	.word	Bool_dispTab
+	.word	0
+	.word	-1
+List_protObj:
+	.word	6
+	.word	3
+	.word	List_dispTab
+	.word	-1
+Cons_protObj:
+	.word	7
+	.word	5
+	.word	Cons_dispTab
+	.word	int_const0
+	.word	0
+	.globl	heap_start
+heap_start:
+	.word	0
+	.text
+	.globl	Main_init
+	.globl	Int_init
+	.globl	String_init
+	.globl	Bool_init
+	.globl	Main.main
+Object_init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+IO_init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	jal	Object_init
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+Main_init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	jal	IO_init
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+Main.print_list:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	lw	$a0 12($fp)
+	bnez	$a0 label3
+	la	$a0 str_const8
+	li	$t1 108
+	jal	_dispatch_abort
+label3:
+	lw	$t1 8($a0)
+	lw	$t1 12($t1)
+	jalr	$t1
+	lw	$t1 12($a0)
+	beqz	$t1 label1
+	la	$a0 str_const10
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	move	$a0 $s0
+	bnez	$a0 label4
+	la	$a0 str_const8
+	li	$t1 108
+	jal	_dispatch_abort
+label4:
+	lw	$t1 8($a0)
+	lw	$t1 12($t1)
+	jalr	$t1
+	b	label2
+label1:
+	lw	$a0 12($fp)
+	bnez	$a0 label5
+	la	$a0 str_const8
+	li	$t1 110
+	jal	_dispatch_abort
+label5:
+	lw	$t1 8($a0)
+	lw	$t1 16($t1)
+	jalr	$t1
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	move	$a0 $s0
+	bnez	$a0 label6
+	la	$a0 str_const8
+	li	$t1 110
+	jal	_dispatch_abort
+label6:
+	lw	$t1 8($a0)
+	lw	$t1 16($t1)
+	jalr	$t1
+	la	$a0 str_const11
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	move	$a0 $s0
+	bnez	$a0 label7
+	la	$a0 str_const8
+	li	$t1 111
+	jal	_dispatch_abort
+label7:
+	lw	$t1 8($a0)
+	lw	$t1 12($t1)
+	jalr	$t1
+	lw	$a0 12($fp)
+	bnez	$a0 label8
+	la	$a0 str_const8
+	li	$t1 112
+	jal	_dispatch_abort
+label8:
+	lw	$t1 8($a0)
+	lw	$t1 20($t1)
+	jalr	$t1
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	move	$a0 $s0
+	bnez	$a0 label9
+	la	$a0 str_const8
+	li	$t1 112
+	jal	_dispatch_abort
+label9:
+	lw	$t1 8($a0)
+	lw	$t1 28($t1)
+	jalr	$t1
+label2:
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 16
+	jr	$ra
+Main.main:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	la	$a0 int_const1
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	la	$a0 int_const2
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	la	$a0 int_const3
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	la	$a0 int_const4
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	la	$a0 int_const5
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	la	$a0 List_protObj
+	jal	Object.copy
+	jal	List_init
+	bnez	$a0 label10
+	la	$a0 str_const8
+	li	$t1 128
+	jal	_dispatch_abort
+label10:
+	lw	$t1 8($a0)
+	lw	$t1 24($t1)
+	jalr	$t1
+	bnez	$a0 label11
+	la	$a0 str_const8
+	li	$t1 128
+	jal	_dispatch_abort
+label11:
+	lw	$t1 8($a0)
+	lw	$t1 24($t1)
+	jalr	$t1
+	bnez	$a0 label12
+	la	$a0 str_const8
+	li	$t1 128
+	jal	_dispatch_abort
+label12:
+	lw	$t1 8($a0)
+	lw	$t1 24($t1)
+	jalr	$t1
+	bnez	$a0 label13
+	la	$a0 str_const8
+	li	$t1 128
+	jal	_dispatch_abort
+label13:
+	lw	$t1 8($a0)
+	lw	$t1 24($t1)
+	jalr	$t1
+	bnez	$a0 label14
+	la	$a0 str_const8
+	li	$t1 128
+	jal	_dispatch_abort
+label14:
+	lw	$t1 8($a0)
+	lw	$t1 24($t1)
+	jalr	$t1
+	sw	$a0 12($s0)
+	addiu	$a1 $s0 12
+	jal	_GenGC_Assign
+label15:
+	lw	$a0 12($s0)
+	bnez	$a0 label17
+	la	$a0 str_const8
+	li	$t1 129
+	jal	_dispatch_abort
+label17:
+	lw	$t1 8($a0)
+	lw	$t1 12($t1)
+	jalr	$t1
+	lw	$t1 12($a0)
+	la	$a0 bool_const1
+	beqz	$t1 label18
+	la	$a0 bool_const0
+label18:
+	lw	$t1 12($a0)
+	beqz	$t1 label16
+	lw	$a0 12($s0)
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	move	$a0 $s0
+	bnez	$a0 label19
+	la	$a0 str_const8
+	li	$t1 131
+	jal	_dispatch_abort
+label19:
+	lw	$t1 8($a0)
+	lw	$t1 28($t1)
+	jalr	$t1
+	lw	$a0 12($s0)
+	bnez	$a0 label20
+	la	$a0 str_const8
+	li	$t1 132
+	jal	_dispatch_abort
+label20:
+	lw	$t1 8($a0)
+	lw	$t1 20($t1)
+	jalr	$t1
+	sw	$a0 12($s0)
+	addiu	$a1 $s0 12
+	jal	_GenGC_Assign
+	b	label15
+label16:
+	move	$a0 $zero
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+Int_init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	jal	Object_init
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+String_init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	jal	Object_init
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+Bool_init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	jal	Object_init
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+List_init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	jal	Object_init
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+List.isNil:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	la	$a0 bool_const1
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+List.head:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	move	$a0 $s0
+	bnez	$a0 label21
+	la	$a0 str_const8
+	li	$t1 35
+	jal	_dispatch_abort
+label21:
+	lw	$t1 8($a0)
+	lw	$t1 0($t1)
+	jalr	$t1
+	la	$a0 int_const0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+List.tail:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	move	$a0 $s0
+	bnez	$a0 label22
+	la	$a0 str_const8
+	li	$t1 40
+	jal	_dispatch_abort
+label22:
+	lw	$t1 8($a0)
+	lw	$t1 0($t1)
+	jalr	$t1
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+List.cons:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	lw	$a0 12($fp)
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	move	$a0 $s0
+	sw	$a0 0($sp)
+	addiu	$sp $sp -4
+	la	$a0 Cons_protObj
+	jal	Object.copy
+	jal	Cons_init
+	bnez	$a0 label23
+	la	$a0 str_const8
+	li	$t1 50
+	jal	_dispatch_abort
+label23:
+	lw	$t1 8($a0)
+	lw	$t1 28($t1)
+	jalr	$t1
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 16
+	jr	$ra
+Cons_init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	jal	List_init
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+Cons.isNil:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	la	$a0 bool_const0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+Cons.head:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	lw	$a0 12($s0)
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+Cons.tail:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	lw	$a0 16($s0)
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 12
+	jr	$ra
+Cons.init:
+	addiu	$sp $sp -12
+	sw	$fp 12($sp)
+	sw	$s0 8($sp)
+	sw	$ra 4($sp)
+	addiu	$fp $sp 4
+	move	$s0 $a0
+	lw	$a0 16($fp)
+	sw	$a0 12($s0)
+	addiu	$a1 $s0 12
+	jal	_GenGC_Assign
+	lw	$a0 12($fp)
+	sw	$a0 16($s0)
+	addiu	$a1 $s0 16
+	jal	_GenGC_Assign
+	move	$a0 $s0
+	lw	$fp 12($sp)
+	lw	$s0 8($sp)
+	lw	$ra 4($sp)
+	addiu	$sp $sp 20
+	jr	$ra
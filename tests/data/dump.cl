@@ -0,0 +1,28 @@
+(* every kind of node of the reference ast dump *)
+class Main inherits IO {
+  count : Int <- 007;
+  flag : Bool;
+
+  main() : Object {
+    {
+      out_string("tab\there \"quoted\" café\n");
+      count <- count + 1 - 2 * 3 / 4;
+      while count <= 10 loop
+        if count = 5 then abort() else count <- count + 1 fi
+      pool;
+      (let y : Int <- ~1, z : Bool in not z);
+    }
+  };
+
+  test(a : Int, o : Object) : Object {
+    case o of
+      n : Int => isvoid n ;
+      s : String => s.length() ;
+      m : Main => m@IO.out_int(a) ;
+    esac
+  };
+};
+
+class Other {
+  o : Other <- new Other;
+};
@@ -0,0 +1,23 @@
+(* every kind of node of the json and s-expression exports *)
+class Main inherits IO {
+  x : Int <- 1;
+  b : Bool;
+
+  main() : Object {
+    {
+      out_string("a\t\"b\"\n");
+      x <- (x + 2) * 3 - 4 / 5;
+      while x < 10 loop x <- x + 1 pool;
+      if x <= 5 then abort() else self@IO.out_int(~x) fi;
+      let y : Int <- 6, z : Bool in not z = (y > 7);
+      case new Main of
+        m : Main => isvoid m;
+        o : Object => delete o;
+      esac;
+      b <- true;
+      x >= 8;
+    }
+  };
+
+  f(a : Int, s : String) : String { s.concat(s) };
+};
@@ -0,0 +1,23 @@
+(*
+ *  greetings.cl
+ *
+ *  A made up program for the tests of non ascii source , it is not a
+ *  real cool program and the names below are invented :
+ *           José Martínez , Zoë Ångström
+ *           François Lefèvre (* and Jürgen Groß in a nested comment *)
+ *  Multibyte characters in comments , strings and the columns after them.
+ *)
+
+class Main inherits IO {
+    -- Grüße , ¡hola! , こんにちは
+    greeting : String <- "Grüße aus Köln";
+
+    main() : Object {
+        {
+            out_string(greeting.concat("\n"));
+            out_string("¿Qué tal?\n");
+            out_int(greeting.length());
+            out_string("\n");
+        }
+    };
+};
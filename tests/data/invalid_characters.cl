@@ -0,0 +1,5 @@
+-- written by Ståle Ødegård
+class Main {
+    naïve : Int <- 1;
+    x : Int <- 2 × 3;
+};
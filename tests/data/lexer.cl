@@ -0,0 +1,13 @@
+-- the corner cases of the reference lexer , the tokens it gives for them are in lexer.lex
+(* a (* nested *) comment
+   over two lines *)
+CLASS Cons InHeRiTs List { tRuE True fALSE false };
+007 0 42 x_1 Y_2 _x __ x__
+<- <= => < = + - * / ~ . @ , : ; ( )
+"tab\there" "quote \" and backslash \\" "\q\b\f"
+"escaped \
+newline"
+"unterminated
+x *) y
+[ ] ! # $ % ^ & ? ` \ |
+(* open to the end
@@ -0,0 +1,64 @@
+use cool_compiler_rs::{Scanner,Parser,Span};
+use cool_compiler_rs::diagnostic::*;
+
+/*
+the scanner and the parser hand back every problem as a Diagnostic instead of printing it ,
+with its code , message and span
+*/
+
+fn summaries(diagnostics:&Diagnostics)->Vec<String>{
+    diagnostics.iter().map(|d| d.to_string()).collect()
+}
+
+#[test]
+fn the_scanner_collects_every_error(){
+    let source = "class A {\n  x : Int <- #;\n  y : Int <- 1 $ 2;\n};\n";
+    let Err(diagnostics) = Scanner::new(source.to_string()).scan_tokens() else{
+        panic!("two invalid characters");
+    };
+    assert_eq!(summaries(&diagnostics),vec![
+        "2:14: error[E0001]: Invalid character '#' (U+0023)",
+        "3:16: error[E0001]: Invalid character '$' (U+0024)"
+    ]);
+    let spans : Vec<Span> = diagnostics.iter().map(|d| d.span).collect();
+    assert_eq!(spans,vec![Span::new(23,24,2,14),Span::new(41,42,3,16)]);
+    assert!(Scanner::new("class A {};".to_string()).scan_tokens().is_ok());
+}
+
+#[test]
+fn the_parser_collects_every_error(){
+    let source = "class A {\n  f() : Int { 1 + };\n  g( : Int { 2 };\n};\nclass B inherits { };\n";
+    let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+    let Err(diagnostics) = Parser::new(tokens).parse_program() else{
+        panic!("three syntax errors");
+    };
+    let codes : Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+    assert_eq!(codes,vec![EXPECTED_EXPRESSION,EXPECTED_TOKEN,EXPECTED_TOKEN]);
+    assert!(diagnostics.iter().all(|d| d.severity == Severity::ERROR));
+    assert_eq!(diagnostics.iter().map(|d| d.span.line).collect::<Vec<usize>>(),vec![2,3,5]);
+}
+
+#[test]
+fn notes_follow_the_summary(){
+    let (_,diagnostics) = Scanner::new("x <- é".to_string()).scan_all();
+    assert_eq!(summaries(&diagnostics),vec![
+        "1:6: error[E0001]: Invalid character 'é' (U+00E9)\n  = note: characters outside of ascii are only allowed in strings and comments"
+    ]);
+}
+
+#[test]
+fn the_sink(){
+    let mut diagnostics = Diagnostics::new();
+    assert!(diagnostics.is_empty() && !diagnostics.has_errors());
+    diagnostics.push(Diagnostic::warning("W0001","a warning".to_string(),Span::new(0,1,1,1)));
+    assert!(!diagnostics.has_errors());
+    let mut more = Diagnostics::new();
+    more.push(Diagnostic::error(EXPECTED_TOKEN,"an error".to_string(),Span::new(2,3,1,3)).with_note("a note".to_string()));
+    diagnostics.extend(more);
+    assert!(diagnostics.has_errors());
+    assert_eq!(diagnostics.len(),2);
+    assert_eq!(summaries(&diagnostics),vec![
+        "1:1: warning[W0001]: a warning",
+        "1:3: error[E0100]: an error\n  = note: a note"
+    ]);
+}
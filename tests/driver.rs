@@ -0,0 +1,122 @@
+mod common;
+
+use std::process::{Command,Output,Stdio};
+
+/*
+the command line driver , its stages , its output and its exit codes
+0 success , 1 fmt --check found unformatted files , 64 bad command line , 65 errors in the program ,
+70 runtime error under --run , 74 file errors
+*/
+
+fn driver(args:&[&str])->Output{
+    Command::new(env!("CARGO_BIN_EXE_cool-compiler-rs"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+//writes a program to a file of its own , the path of that file
+fn program(name:&str,source:&str)->String{
+    let path = format!("{}/{}",env!("CARGO_TARGET_TMPDIR"),name);
+    std::fs::write(&path,source).unwrap();
+    path
+}
+
+fn stdout(output:&Output)->String{
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output:&Output)->String{
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn success(){
+    let output = driver(&["--semant","examples/hello_world.cl"]);
+    assert_eq!((output.status.code(),stdout(&output),stderr(&output)),(Some(0),String::new(),String::new()));
+    let output = driver(&["--run","examples/hello_world.cl"]);
+    assert_eq!((output.status.code(),stdout(&output)),(Some(0),"Hello, World.\n".to_string()));
+    //the classes of every input are one program , atoi_test.cl uses A2I from atoi.cl
+    assert_eq!(driver(&["--semant","examples/atoi.cl","examples/atoi_test.cl"]).status.code(),Some(0));
+}
+
+#[test]
+fn codegen_writes_the_assembly(){
+    let out = format!("{}/driver_hello_world.s",env!("CARGO_TARGET_TMPDIR"));
+    let output = driver(&["-o",&out,"examples/hello_world.cl"]);
+    assert_eq!(output.status.code(),Some(0),"{}",stderr(&output));
+    assert_eq!(std::fs::read_to_string(&out).unwrap(),common::read("tests/data/asm/hello_world.s"));
+}
+
+#[test]
+fn bad_command_lines(){
+    let output = driver(&["--bogus","examples/hello_world.cl"]);
+    assert_eq!(output.status.code(),Some(64));
+    assert!(stderr(&output).starts_with("unknown option --bogus\nUsage: cool-compiler-rs"),"{}",stderr(&output));
+    assert_eq!(driver(&["--lex","--parse","examples/hello_world.cl"]).status.code(),Some(64));
+    assert_eq!(driver(&["-o"]).status.code(),Some(64));
+}
+
+#[test]
+fn errors_in_the_program(){
+    let output = driver(&["--semant","tests/data/invalid_characters.cl"]);
+    assert_eq!(output.status.code(),Some(65));
+    assert!(stderr(&output).ends_with("Compilation halted due to lex and parse errors\n"),"{}",stderr(&output));
+    let path = program("driver_syntax.cl","class Main {\n  main() : Int { 1 + };\n};\n");
+    assert_eq!(driver(&["--parse",&path]).status.code(),Some(65));
+    let path = program("driver_semant.cl","class Main {\n  main() : Int { true };\n};\n");
+    let output = driver(&["--semant",&path]);
+    assert_eq!(output.status.code(),Some(65));
+    assert!(stderr(&output).ends_with("Compilation halted due to static semantic errors.\n"),"{}",stderr(&output));
+}
+
+#[test]
+fn runtime_errors(){
+    let path = program("driver_runtime.cl","class Main inherits IO {\n  main() : Object { out_int(1 / 0) };\n};\n");
+    let output = driver(&["--run",&path]);
+    assert_eq!(output.status.code(),Some(70));
+    assert_eq!(stderr(&output),format!("{}:2: Division by zero.\n",path));
+}
+
+#[test]
+fn file_errors(){
+    let output = driver(&["--semant","tests/data/no_such_file.cl"]);
+    assert_eq!(output.status.code(),Some(74));
+    assert!(stderr(&output).starts_with("Error reading file tests/data/no_such_file.cl: "),"{}",stderr(&output));
+}
+
+#[test]
+fn fmt_check(){
+    let path = program("driver_fmt.cl","class Main{main():Int{0};};\n");
+    let output = driver(&["fmt","--check",&path]);
+    assert_eq!((output.status.code(),stdout(&output) + &stderr(&output)),(Some(1),format!("{} is not formatted\n",path)));
+    assert_eq!(driver(&["fmt",&path]).status.code(),Some(0));
+    assert_eq!(driver(&["fmt","--check",&path]).status.code(),Some(0));
+}
+
+//the prompt prints the ast of each line and renders its errors like the files , without the tokens
+#[test]
+fn prompt(){
+    use std::io::Write;
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cool-compiler-rs"))
+        .env("NO_COLOR","1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"class A { x : Int <- # ; };\nclass C {};\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(),Some(0));
+    assert_eq!(stderr(&output),"\
+error[E0001]: Invalid character '#' (U+0023)
+ --> <stdin>:1:22
+  |
+1 | class A { x : Int <- # ; };
+  |                      ^
+");
+    assert!(stdout(&output).contains("class C {\n};\n"),"{}",stdout(&output));
+    assert!(!stdout(&output).contains("Token"),"{}",stdout(&output));
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use cool_compiler_rs::{Scanner,Parser,TriviaKind};
+use cool_compiler_rs::astprinter::AstPrinter;
+use cool_compiler_rs::formatter::{format,FormatOptions};
+
+fn examples()->Vec<(String,String)>{
+    let mut sources = vec![("tests/data/greetings.cl".to_string(),common::read("tests/data/greetings.cl"))];
+    sources.extend(common::examples());
+    sources
+}
+
+fn ast(source:&str)->String{
+    let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+    AstPrinter::print_program(&Parser::new(tokens).parse_program().unwrap())
+}
+
+fn comments(source:&str)->Vec<String>{
+    let (tokens,_) = Scanner::new(source.to_string()).with_trivia().scan_all();
+    tokens.iter()
+        .flat_map(|t| t.leading.iter().chain(t.trailing.iter()))
+        .filter(|t| matches!(t.kind,TriviaKind::LINECOMMENT|TriviaKind::BLOCKCOMMENT))
+        .map(|t| t.text.trim_end().to_string())
+        .collect()
+}
+
+fn fmt(source:&str)->String{
+    format(source,&FormatOptions::default()).unwrap()
+}
+
+#[test]
+fn formats_every_example_idempotently(){
+    for options in [FormatOptions::default(),FormatOptions{indent:2,width:40},FormatOptions{indent:3,width:120}]{
+        for (path,source) in examples(){
+            let formatted = format(&source,&options).unwrap();
+            assert_eq!(format(&formatted,&options).unwrap(),formatted,"{} {:?}",path,options);
+            assert_eq!(ast(&formatted),ast(&source),"{} {:?}",path,options);
+        }
+    }
+}
+
+#[test]
+fn keeps_the_program_and_its_comments(){
+    for (path,source) in examples(){
+        let formatted = fmt(&source);
+        assert_eq!(ast(&formatted),ast(&source),"{}",path);
+        assert_eq!(comments(&formatted),comments(&source),"{}",path);
+    }
+}
+
+#[test]
+fn canonical_layout(){
+    let source = "class Main inherits IO{x:Int<-1;main():Object{{out_string(\"hi\\n\");x<-x+1;}};};class A{};";
+    assert_eq!(fmt(source),"\
+class Main inherits IO {
+    x : Int <- 1;
+    main() : Object { { out_string(\"hi\\n\"); x <- x + 1; } };
+};
+
+class A {};
+");
+}
+
+#[test]
+fn breaks_what_does_not_fit(){
+    let source = "class Main { f(x : Int, y : Int) : Int { if x < y then let z : Int <- x + y in z * z else y fi }; };";
+    let options = FormatOptions{indent:2,width:40};
+    let formatted = format(source,&options).unwrap();
+    assert_eq!(formatted,"\
+class Main {
+  f(x : Int, y : Int) : Int {
+    if x < y then
+      let z : Int <- x + y in z * z
+    else
+      y
+    fi
+  };
+};
+");
+    assert!(formatted.lines().all(|l| l.chars().count() <= 40));
+    assert_eq!(format(&formatted,&options).unwrap(),formatted);
+}
+
+#[test]
+fn keeps_comments_and_blank_lines(){
+    let source = "-- header\n\nclass Main {\n\n\n  -- the answer\n  x : Int <- 42; -- forty two\n\n\n\n  y : Int;\n  (* end *)\n};\n-- trailer\n";
+    assert_eq!(fmt(source),"\
+-- header
+
+class Main {
+    -- the answer
+    x : Int <- 42; -- forty two
+
+    y : Int;
+    (* end *)
+};
+-- trailer
+");
+}
+
+#[test]
+fn line_comments_end_the_line(){
+    let source = "class Main { f() : Int { { 1; -- one\n 2; } }; };";
+    assert_eq!(fmt(source),"\
+class Main {
+    f() : Int {
+        {
+            1; -- one
+            2;
+        }
+    };
+};
+");
+}
+
+#[test]
+fn refuses_source_with_errors(){
+    let diagnostics = format("class Main { x : Int <- ; };",&FormatOptions::default()).unwrap_err();
+    assert!(diagnostics.has_errors());
+    assert!(format("class Main { x : Int <- \"open };",&FormatOptions::default()).is_err());
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use cool_compiler_rs::{Scanner,Parser,semant};
+use cool_compiler_rs::interpreter::Interpreter;
+use common::parse;
+
+/*
+programs run by the interpreter on a given input , what they print and the runtime error they stop with ,
+reported the way the driver prints it
+*/
+
+fn run_program(program:cool_compiler_rs::ast::Program,input:&str)->(String,Result<(),String>){
+    let Ok(analysis) = semant::check(&program) else{
+        panic!("the program has semantic errors");
+    };
+    let mut output = vec![];
+    let result = Interpreter::new(&program,&analysis.table,Box::new(input.as_bytes()),Box::new(&mut output)).run();
+    (String::from_utf8(output).unwrap(),result.map_err(|e| e.report()))
+}
+
+fn run(source:&str,input:&str)->(String,Result<(),String>){
+    let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+    let mut program = Parser::new(tokens).parse_program().unwrap();
+    program.set_filename("test.cl");
+    run_program(program,input)
+}
+
+//a Main whose main method has the given body , on line 1
+fn main(body:&str)->String{
+    format!("class Main inherits IO {{ main() : Object {{ {} }}; }};\n",body)
+}
+
+#[test]
+fn hello_world(){
+    assert_eq!(run_program(parse("examples/hello_world.cl"),""),("Hello, World.\n".to_string(),Ok(())));
+}
+
+#[test]
+fn reads_the_input(){
+    let source = main("{ out_int(in_int() + 1); out_string(in_string().concat(\"!\")); }");
+    assert_eq!(run(&source,"41\nhello\n"),("42hello!".to_string(),Ok(())));
+    //in_int reads 0 from a line that is not a number , and at the end of the input
+    assert_eq!(run(&main("out_int(in_int() + in_int())"),"x\n"),("0".to_string(),Ok(())));
+}
+
+#[test]
+fn strings(){
+    let source = main("{ out_int(\"hello\".length()); out_string(\"hello\".substr(1, 3)); out_string(\"ab\".substr(2, 0)); }");
+    assert_eq!(run(&source,""),("5ell".to_string(),Ok(())));
+}
+
+#[test]
+fn substr_out_of_range(){
+    let error = Err("test.cl:1: Index to substr is out of range".to_string());
+    for (start,length) in [("2","2"),("~1","1"),("0","~1"),("2147483647","1"),("1","2147483647")]{
+        let source = main(&format!("out_string(\"abc\".substr({}, {}))",start,length));
+        assert_eq!(run(&source,""),(String::new(),error.clone()),"{} {}",start,length);
+    }
+}
+
+#[test]
+fn objects_are_shared(){
+    let source = "\
+class Counter { n : Int; inc() : Counter { { n <- n + 1; self; } }; get() : Int { n }; };
+class Main inherits IO {
+  main() : Object { let a : Counter <- new Counter, b : Counter <- a in { a.inc(); b.inc(); out_int(a.get()); } };
+};
+";
+    assert_eq!(run(source,""),("2".to_string(),Ok(())));
+}
+
+#[test]
+fn runtime_errors(){
+    assert_eq!(run(&main("{ out_string(\"x\"); (let a : Main in a).out_int(1); }"),""),
+        ("x".to_string(),Err("test.cl:1: Dispatch to void.".to_string())));
+    assert_eq!(run(&main("1 / 0"),""),(String::new(),Err("test.cl:1: Division by zero.".to_string())));
+    assert_eq!(run(&main("case (let a : Main in a) of m : Main => m; esac"),""),
+        (String::new(),Err("test.cl:1: Match on void in case statement.".to_string())));
+    assert_eq!(run(&main("case 1 of m : Main => m; esac"),""),
+        (String::new(),Err("test.cl:1: No match in case statement for Class Int".to_string())));
+    assert_eq!(run(&main("{ out_string(\"before\"); abort(); out_string(\"after\"); }"),""),
+        ("before".to_string(),Err("Abort called from class Main".to_string())));
+}
+
+#[test]
+fn deep_recursion_is_a_runtime_error(){
+    let source = "class Main { main() : Object { f(0) }; f(n : Int) : Int { f(n + 1) }; };\n";
+    let (_,result) = std::thread::scope(|scope|{
+        std::thread::Builder::new().stack_size(1 << 30).spawn_scoped(scope,|| run(source,"")).unwrap().join().unwrap()
+    });
+    assert_eq!(result,Err("test.cl:1: Stack overflow.".to_string()));
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use cool_compiler_rs::{Scanner,Parser};
+use cool_compiler_rs::ast::Expr;
+use cool_compiler_rs::astdump::AstDump;
+use cool_compiler_rs::cst::{SyntaxKind,SyntaxNode};
+
+/*
+every pair of operators against the precedence table of the cool manual . the expected grouping is
+worked out from the table below , the parsed ast is printed fully parenthesized and compared to it
+*/
+
+//operator , level (higher binds tighter) , left associative (comparisons are non associative)
+//<- is lowest (1) , right associative , and only takes a name on its left , it is tested on its own
+const BINARY:[(&str,u8,bool);9] = [
+    ("<=",3,false),
+    ("<",3,false),
+    ("=",3,false),
+    (">",3,false),
+    (">=",3,false),
+    ("+",4,true),
+    ("-",4,true),
+    ("*",5,true),
+    ("/",5,true)
+];
+
+const PREFIX:[(&str,u8);3] = [("not ",2),("isvoid ",6),("~",7)];
+
+fn print(expr:&Expr)->String{
+    match expr{
+        Expr::Assign{left,right} => format!("({} <- {})",print(left),print(right)),
+        Expr::Comparison{left,operator,right}|Expr::Arithmetic{left,operator,right}|Expr::Factor{left,operator,right} =>
+            format!("({} {} {})",print(left),operator.lexeme,print(right)),
+        Expr::Not{expr,..} => format!("(not {})",print(expr)),
+        Expr::IsVoid{expr,..} => format!("(isvoid {})",print(expr)),
+        Expr::BitWiseNot{expr,..} => format!("(~{})",print(expr)),
+        Expr::Grouping{expr,..} => print(expr),
+        Expr::ID{id} => id.lexeme.clone(),
+        Expr::IntegerLiteral{value}|Expr::BoolLiteral{value} => value.lexeme.clone(),
+        Expr::Let{declarations,body,..} =>{
+            let names : Vec<String> = declarations.iter().map(print).collect();
+            format!("(let {} in {})",names.join(", "),print(body))
+        },
+        Expr::Declaration{id,expr,..} => match expr.as_ref(){
+            Some(init) => format!("{} <- {}",id.lexeme,print(init)),
+            None => id.lexeme.clone()
+        },
+        Expr::Dispatch{target,expr,method_name,..} =>{
+            let target = target.as_ref().map_or(String::new(),|t| format!("@{}",t.lexeme));
+            let method = method_name.as_ref().map_or(String::new(),|m| format!(".{}",m.lexeme));
+            format!("{}{}{}()",print(expr),target,method)
+        },
+        other => panic!("unexpected {:?}",other)
+    }
+}
+
+fn parse(source:&str)->Result<String,Vec<String>>{
+    let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+    match Parser::new(tokens).parse_expression(){
+        Ok(expr) => Ok(print(&expr)),
+        Err(diagnostics) => Err(diagnostics.iter().map(|d| d.code.to_string()).collect())
+    }
+}
+
+fn binary()->impl Iterator<Item=(&'static str,u8,bool)>{
+    BINARY.into_iter()
+}
+
+#[test]
+fn every_pair_of_binary_operators(){
+    for (first,first_level,left) in binary(){
+        for (second,second_level,_) in binary(){
+            let source = format!("a {} b {} c",first,second);
+            let expected = if first_level > second_level || (first_level == second_level && left){
+                Ok(format!("((a {} b) {} c)",first,second))
+            }
+            else if first_level < second_level{
+                Ok(format!("(a {} (b {} c))",first,second))
+            }
+            else{
+                Err(vec!["E0109".to_string()])
+            };
+            assert_eq!(parse(&source),expected,"{}",source);
+        }
+    }
+}
+
+#[test]
+fn every_prefix_operator_against_every_binary_operator(){
+    for (prefix,prefix_level) in PREFIX{
+        let name = prefix.trim();
+        for (op,level,_) in binary(){
+            let source = format!("{}a {} b",prefix,op);
+            let expected = if prefix_level > level{
+                format!("(({}{}a) {} b)",name,if name == "~" {""} else {" "},op)
+            }
+            else{
+                format!("({} (a {} b))",name,op)
+            };
+            assert_eq!(parse(&source).unwrap(),expected,"{}",source);
+            //a prefix operator can always start the right operand
+            let source = format!("a {} {}b",op,prefix);
+            let expected = format!("(a {} ({}{}b))",op,name,if name == "~" {""} else {" "});
+            assert_eq!(parse(&source).unwrap(),expected,"{}",source);
+        }
+    }
+}
+
+#[test]
+fn prefix_operators_nest(){
+    assert_eq!(parse("not isvoid ~a").unwrap(),"(not (isvoid (~a)))");
+    assert_eq!(parse("~isvoid a").unwrap(),"(~(isvoid a))");
+    assert_eq!(parse("not not a = b").unwrap(),"(not (not (a = b)))");
+    assert_eq!(parse("~~a + b").unwrap(),"((~(~a)) + b)");
+}
+
+#[test]
+fn assignment_is_lowest_and_right_associative(){
+    for (op,_,_) in binary(){
+        let source = format!("x <- a {} b",op);
+        assert_eq!(parse(&source).unwrap(),format!("(x <- (a {} b))",op),"{}",source);
+    }
+    assert_eq!(parse("x <- y <- z").unwrap(),"(x <- (y <- z))");
+    assert_eq!(parse("x <- not a < b").unwrap(),"(x <- (not (a < b)))");
+    assert_eq!(parse("a.f() <- c"),Err(vec!["E0106".to_string()]));
+    assert_eq!(parse("(a) <- c"),Err(vec!["E0106".to_string()]));
+}
+
+#[test]
+fn assignment_extends_right_from_any_operand(){
+    assert_eq!(parse("a + b <- c").unwrap(),"(a + (b <- c))");
+    assert_eq!(parse("1 + x <- 2 * 3 < 4").unwrap(),"(1 + (x <- ((2 * 3) < 4)))");
+    assert_eq!(parse("a * b <- c + d").unwrap(),"(a * (b <- (c + d)))");
+    assert_eq!(parse("a < b <- c < d").unwrap(),"(a < (b <- (c < d)))");
+    assert_eq!(parse("not x <- 1").unwrap(),"(not (x <- 1))");
+    assert_eq!(parse("~x <- 1 + 2").unwrap(),"(~(x <- (1 + 2)))");
+    assert_eq!(parse("isvoid x <- y").unwrap(),"(isvoid (x <- y))");
+    assert_eq!(parse("a + b <- c <- d + e").unwrap(),"(a + (b <- (c <- (d + e))))");
+}
+
+#[test]
+fn let_extends_right_from_any_operand(){
+    assert_eq!(parse("1 + let y : Int in y + 1 < 2").unwrap(),"(1 + (let y in ((y + 1) < 2)))");
+    assert_eq!(parse("x <- let y : Int <- 1 in y * 2").unwrap(),"(x <- (let y <- 1 in (y * 2)))");
+    assert_eq!(parse("let y : Int in y <- 1 + 2").unwrap(),"(let y in (y <- (1 + 2)))");
+    assert_eq!(parse("not let y : Bool in y = false").unwrap(),"(not (let y in (y = false)))");
+    assert_eq!(parse("~let y : Int in y + 1").unwrap(),"(~(let y in (y + 1)))");
+    assert_eq!(parse("a < let y : Int, z : Int in y < z").unwrap(),"(a < (let y, z in (y < z)))");
+    assert_eq!(parse("(let y : Int in y) + 1").unwrap(),"((let y in y) + 1)");
+}
+
+#[test]
+fn not_extends_right_from_any_operand(){
+    assert_eq!(parse("a * not b + c").unwrap(),"(a * (not (b + c)))");
+    assert_eq!(parse("a < not b < c").unwrap(),"(a < (not (b < c)))");
+    assert_eq!(parse("a + not b <- c").unwrap(),"(a + (not (b <- c)))");
+}
+
+//in the grammar of the reference parser let , <- and not take everything to their right , so none of them
+//can end before an operator or a dispatch unless it is parenthesized
+fn check_extends_right(node:&SyntaxNode,path:&str){
+    let open = [SyntaxKind::LET_EXPR,SyntaxKind::ASSIGN_EXPR,SyntaxKind::NOT_EXPR];
+    if matches!(node.kind(),SyntaxKind::BINARY_EXPR|SyntaxKind::DISPATCH_EXPR){
+        if let Some(left) = node.children().first(){
+            assert!(!open.contains(&left.kind()),"{}: {:?} before an operator in {}",path,left.kind(),node.text().trim());
+        }
+    }
+    for child in node.children(){
+        check_extends_right(&child,path);
+    }
+}
+
+#[test]
+fn examples_follow_the_reference_grammar(){
+    for (path,source) in common::examples(){
+        let mut scanner = Scanner::new(source).with_trivia();
+        let (tree,diagnostics) = Parser::new(&mut scanner).parse_cst();
+        assert!(!diagnostics.has_errors(),"{}",path);
+        check_extends_right(&tree,&path);
+    }
+}
+
+//examples where let , <- and not take everything to their right , as coolc -p prints their ast .
+//primes.cl assigns sums in loops , cells.cl nests lets around while and if , graph.cl has not line = ""
+#[test]
+fn examples_group_like_the_reference_parser(){
+    for name in ["primes","cells","graph"]{
+        let dump = AstDump::dump_program(&common::parse(&format!("examples/{}.cl",name)));
+        assert!(dump == common::read(&format!("tests/data/ast/{}.ast",name)),"{}",name);
+    }
+}
+
+#[test]
+fn dispatch_binds_tightest(){
+    assert_eq!(parse("~a.f() + b").unwrap(),"((~a.f()) + b)");
+    assert_eq!(parse("isvoid a@B.f() * b").unwrap(),"((isvoid a@B.f()) * b)");
+    assert_eq!(parse("a + b.f().g()").unwrap(),"(a + b.f().g())");
+}
+
+#[test]
+fn comparisons_do_not_chain(){
+    assert_eq!(parse("a < b < c"),Err(vec!["E0109".to_string()]));
+    assert_eq!(parse("a = b <= c"),Err(vec!["E0109".to_string()]));
+    assert_eq!(parse("(a < b) < c").unwrap(),"((a < b) < c)");
+    assert_eq!(parse("a < (b < c)").unwrap(),"(a < (b < c))");
+    assert_eq!(parse("not a < b = c"),Err(vec!["E0109".to_string()]));
+    assert_eq!(parse("a = not b < c").unwrap(),"(a = (not (b < c)))");
+    assert_eq!(parse("a + b < c * d").unwrap(),"((a + b) < (c * d))");
+}
+
+#[test]
+fn arithmetic_is_left_associative(){
+    assert_eq!(parse("a - b - c").unwrap(),"((a - b) - c)");
+    assert_eq!(parse("a / b / c").unwrap(),"((a / b) / c)");
+    assert_eq!(parse("a - b * c - d").unwrap(),"((a - (b * c)) - d)");
+    assert_eq!(parse("~a + b").unwrap(),"((~a) + b)");
+}
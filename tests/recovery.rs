@@ -0,0 +1,57 @@
+use cool_compiler_rs::{Scanner,Parser};
+
+/*
+after a syntax error the parser skips to the next feature , expression of a block or class and goes on ,
+so one run reports every independent error of a file and nothing that only follows from an earlier one
+*/
+
+fn errors(source:&str)->Vec<String>{
+    let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+    match Parser::new(tokens).parse_program(){
+        Ok(_) => vec![],
+        Err(diagnostics) => diagnostics.iter().map(|d| d.to_string()).collect()
+    }
+}
+
+#[test]
+fn one_error_per_feature(){
+    assert_eq!(errors("class A {\n  x : Int <- ;\n  y : Int <- 1;\n  z : <- 2;\n};\n"),vec![
+        "2:14: error[E0108]: Expected expression",
+        "4:7: error[E0100]: Expect type name"
+    ]);
+    assert_eq!(errors("class A {\n  f() : Int { if 1 then 2 fi };\n  g() : Int { while 1 loop 2 };\n  h() : Int { 3 };\n};\n"),vec![
+        "2:27: error[E0100]: expect else after then",
+        "3:30: error[E0100]: Expected 'pool' after body"
+    ]);
+}
+
+#[test]
+fn one_error_per_expression_of_a_block(){
+    assert_eq!(errors("class A {\n  f() : Int { {\n    1 + ;\n    2;\n    let x : in x;\n    3;\n  } };\n};\n"),vec![
+        "3:9: error[E0108]: Expected expression",
+        "5:13: error[E0100]: Expect type after identifier in Let expression"
+    ]);
+}
+
+#[test]
+fn one_error_per_class(){
+    assert_eq!(errors("class A inherits {\n};\nclass B {\n  f() : Int { 1 };\n};\nclass C { g() : Int { 2 + } ; };\n"),vec![
+        "1:18: error[E0100]: Expected superclass name",
+        "6:27: error[E0108]: Expected expression"
+    ]);
+    //a missing ; after a class is reported once , the next class is still read
+    assert_eq!(errors("class A {\n  f() : Int { ( 1 + ) };\n  g() : Int { 2 };\n}\nclass B { x : };\n"),vec![
+        "2:21: error[E0108]: Expected expression",
+        "5:1: error[E0100]: Expected ; after class definition",
+        "5:15: error[E0100]: Expect type name"
+    ]);
+}
+
+//braces opened after an error are skipped as a whole
+#[test]
+fn nested_braces_are_skipped(){
+    assert_eq!(errors("class A {\n  f() : Int { 1 + + { 2; { 3; }; } };\n  g() : Int { 4 };\n  h : ;\n};\n"),vec![
+        "2:19: error[E0108]: Expected expression",
+        "4:7: error[E0100]: Expect type name"
+    ]);
+}
@@ -0,0 +1,78 @@
+use cool_compiler_rs::{Diagnostic,Span};
+use cool_compiler_rs::diagnostic::{EXPECTED_TOKEN,INVALID_CHARACTER};
+use cool_compiler_rs::render::Renderer;
+
+/*
+the source line , the carets under the span and the notes of a rendered diagnostic
+*/
+
+const SOURCE:&str = "class Main {\n  main() : Int { if 1 then 2 fi };\n};\n";
+
+fn render(source:&str,diagnostic:&Diagnostic)->String{
+    Renderer::new("test.cl",source,false).render(diagnostic)
+}
+
+#[test]
+fn carets_under_the_span(){
+    let diagnostic = Diagnostic::error(EXPECTED_TOKEN,"expect else after then".to_string(),Span::new(42,44,2,30));
+    assert_eq!(render(SOURCE,&diagnostic),"\
+error[E0100]: expect else after then
+ --> test.cl:2:30
+  |
+2 |   main() : Int { if 1 then 2 fi };
+  |                              ^^
+");
+}
+
+#[test]
+fn notes_under_the_snippet(){
+    let source = "x <- é;\n";
+    let diagnostic = Diagnostic::error(INVALID_CHARACTER,"Invalid character 'é' (U+00E9)".to_string(),Span::new(5,7,1,6))
+        .with_note("characters outside of ascii are only allowed in strings and comments".to_string());
+    assert_eq!(render(source,&diagnostic),"\
+error[E0001]: Invalid character 'é' (U+00E9)
+ --> test.cl:1:6
+  |
+1 | x <- é;
+  |      ^
+  = note: characters outside of ascii are only allowed in strings and comments
+");
+}
+
+#[test]
+fn tabs_keep_the_carets_in_line(){
+    let source = "\tx <-\t#;\n";
+    let diagnostic = Diagnostic::error(INVALID_CHARACTER,"Invalid character '#' (U+0023)".to_string(),Span::new(6,7,1,7));
+    assert!(render(source,&diagnostic).ends_with("1 | \tx <-\t#;\n  | \t    \t^\n"));
+}
+
+//a span over several lines is underlined to the end of its first line
+#[test]
+fn spans_over_several_lines(){
+    let source = "\n\n\n\n\n\n\n\n\nx <- \"open\nstill open\";\n";
+    let diagnostic = Diagnostic::error(EXPECTED_TOKEN,"a long span".to_string(),Span::new(14,34,10,6));
+    assert_eq!(render(source,&diagnostic),"\
+error[E0100]: a long span
+  --> test.cl:10:6
+   |
+10 | x <- \"open
+   |      ^^^^^
+");
+}
+
+//the spans of tokens the parser made up have no line
+#[test]
+fn diagnostics_without_a_line(){
+    let diagnostic = Diagnostic::error(EXPECTED_TOKEN,"Expected class definition".to_string(),Span::default());
+    assert_eq!(render(SOURCE,&diagnostic),"error[E0100]: Expected class definition\n--> test.cl\n");
+}
+
+#[test]
+fn colors(){
+    let diagnostic = Diagnostic::error(EXPECTED_TOKEN,"expect else after then".to_string(),Span::new(42,44,2,30));
+    let rendered = Renderer::new("test.cl",SOURCE,true).render(&diagnostic);
+    assert!(rendered.starts_with("\x1b[1;31merror[E0100]\x1b[0m\x1b[1m: expect else after then\x1b[0m\n"),"{:?}",rendered);
+    assert!(rendered.ends_with("\x1b[1;31m^^\x1b[0m\n"),"{:?}",rendered);
+    assert_eq!(rendered.replace("\x1b[1;31m","").replace("\x1b[1;34m","").replace("\x1b[1m","").replace("\x1b[0m",""),
+        render(SOURCE,&diagnostic));
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use cool_compiler_rs::{Scanner,Token,TokenType,Span,Literal};
+use cool_compiler_rs::TokenType::*;
+use cool_compiler_rs::tokenprinter::TokenPrinter;
+
+fn scan(source:&str)->Vec<Token>{
+    let (tokens,_) = Scanner::new(source.to_string()).scan_all();
+    tokens
+}
+
+fn types(source:&str)->Vec<TokenType>{
+    scan(source).iter().map(|t| t.tokentype).collect()
+}
+
+#[test]
+fn spans_are_byte_offsets(){
+    let tokens = scan("class Main {\n  x : Int <- 42;\n};");
+    let x = &tokens[3];
+    assert_eq!(x.lexeme,"x");
+    assert_eq!(x.span,Span::new(15,16,2,3));
+    let int = &tokens[7];
+    assert_eq!(int.int_value(),42);
+    assert_eq!(int.span,Span::new(26,28,2,14));
+}
+
+#[test]
+fn tokens_at_end_of_input(){
+    assert_eq!(types("\"text\""),vec![STRING,EOF]);
+    assert_eq!(types("x <- y"),vec![OBJECTID,ASSIGN,OBJECTID,EOF]);
+    assert_eq!(types("a <= b -- comment"),vec![OBJECTID,LESSEQUAL,OBJECTID,EOF]);
+    assert_eq!(types("(* (* nested *) still a comment *) x"),vec![OBJECTID,EOF]);
+}
+
+#[test]
+fn unterminated_comment_points_at_its_start(){
+    let (tokens,diagnostics) = Scanner::new("x\n(* open (* nested *)\n".to_string()).scan_all();
+    assert_eq!(tokens[1].tokentype,ERROR);
+    assert_eq!(tokens[1].string_value(),"EOF in comment");
+    assert_eq!(tokens[1].line,3);
+    let error = diagnostics.iter().next().unwrap();
+    assert_eq!(error.span,Span::new(2,4,2,1));
+}
+
+#[test]
+fn multi_megabyte_input(){
+    let class = "class A inherits IO { f(x : Int) : Int { x * 2 }; -- twice\n};\n";
+    let per_class = types(class).len() - 1;
+    let repeats = (4 << 20) / class.len() + 1;
+    let tokens = scan(&class.repeat(repeats));
+    assert_eq!(tokens.len(),per_class * repeats + 1);
+    assert_eq!(tokens[tokens.len() - 2].line,2 * repeats);
+}
+
+//the corner cases of the reference lexer , lexer.lex is what it prints for them
+#[test]
+fn the_tokens_of_the_reference_lexer(){
+    let tokens = scan(&common::read("tests/data/lexer.cl"));
+    assert_eq!(TokenPrinter::print_tokens("tests/data/lexer.cl",&tokens),common::read("tests/data/lexer.lex"));
+}
+
+#[test]
+fn unmatched_comment_end(){
+    let (tokens,diagnostics) = Scanner::new("x *) (* a *) *)".to_string()).scan_all();
+    assert_eq!(tokens.iter().map(|t| t.tokentype).collect::<Vec<TokenType>>(),vec![OBJECTID,ERROR,ERROR,EOF]);
+    assert_eq!(tokens[1].string_value(),"Unmatched *)");
+    assert_eq!(diagnostics.iter().map(|d| d.span).collect::<Vec<Span>>(),vec![Span::new(2,4,1,3),Span::new(13,15,1,14)]);
+    //a * before a ) that does not follow it is still a multiplication
+    assert_eq!(types("(x * )"),vec![LEFTPAREN,OBJECTID,STAR,RIGHTPAREN,EOF]);
+}
+
+#[test]
+fn identifiers_start_with_a_letter(){
+    let (tokens,diagnostics) = Scanner::new("_x x_ _".to_string()).scan_all();
+    assert_eq!(tokens.iter().map(|t| (t.tokentype,t.lexeme.as_str())).collect::<Vec<(TokenType,&str)>>(),
+        vec![(ERROR,"_"),(OBJECTID,"x"),(OBJECTID,"x_"),(ERROR,"_"),(EOF,"\0")]);
+    assert_eq!(diagnostics.iter().map(|d| d.to_string()).collect::<Vec<String>>(),vec![
+        "1:1: error[E0001]: Invalid character '_' (U+005F)",
+        "1:7: error[E0001]: Invalid character '_' (U+005F)"
+    ]);
+}
+
+//type , value and line of every token but EOF , the value is the message of an ERROR
+fn strings(source:&str)->Vec<(TokenType,String,usize)>{
+    scan(source).iter().filter(|t| t.tokentype != EOF).map(|t|{
+        let value = match t.tokentype{
+            STRING|ERROR => t.string_value().to_string(),
+            _ => t.lexeme.clone()
+        };
+        (t.tokentype,value,t.line)
+    }).collect()
+}
+
+fn string(value:&str,line:usize)->(TokenType,String,usize){
+    (STRING,value.to_string(),line)
+}
+
+fn error(message:&str,line:usize)->(TokenType,String,usize){
+    (ERROR,message.to_string(),line)
+}
+
+#[test]
+fn escapes_are_decoded(){
+    assert_eq!(strings(r#""a\nb\tc\bd\fe\qf\\g\"h""#),vec![string("a\nb\tc\u{8}d\u{c}eqf\\g\"h",1)]);
+    //an escaped newline is a newline , the string ends on the next line
+    assert_eq!(strings("\"one\\\ntwo\" x"),vec![string("one\ntwo",2),(OBJECTID,"x".to_string(),2)]);
+    assert_eq!(scan(r#""a\tb""#)[0].lexeme,r#""a\tb""#);
+}
+
+#[test]
+fn unterminated_strings(){
+    //scanning goes on at the start of the next line
+    assert_eq!(strings("\"abc\nx \"y\""),vec![
+        error("Unterminated string constant",2),
+        (OBJECTID,"x".to_string(),2),
+        string("y",2)
+    ]);
+    assert_eq!(strings("x \"abc"),vec![(OBJECTID,"x".to_string(),1),error("EOF in string constant",1)]);
+    assert_eq!(strings("\"abc\\"),vec![error("EOF in string constant",1)]);
+    let (_,diagnostics) = Scanner::new("\"abc\nx".to_string()).scan_all();
+    assert_eq!(diagnostics.iter().map(|d| (d.code,d.span)).collect::<Vec<(&str,Span)>>(),vec![("E0002",Span::new(0,4,1,1))]);
+}
+
+#[test]
+fn null_characters(){
+    //the rest of the string is skipped , scanning goes on after its closing quote
+    assert_eq!(strings("\"a\0b\" x"),vec![error("String contains null character.",1),(OBJECTID,"x".to_string(),1)]);
+    assert_eq!(strings("\"a\\\0b\" x"),vec![error("String contains escaped null character.",1),(OBJECTID,"x".to_string(),1)]);
+    //a null before an unescaped newline is reported instead of the newline , scanning goes on at the next line
+    assert_eq!(strings("\"a\0b\nx"),vec![error("String contains null character.",2),(OBJECTID,"x".to_string(),2)]);
+    let (_,diagnostics) = Scanner::new("\"a\0b\0\"".to_string()).scan_all();
+    assert_eq!(diagnostics.iter().map(|d| (d.code,d.span)).collect::<Vec<(&str,Span)>>(),vec![("E0003",Span::new(2,3,1,3))]);
+}
+
+//the limit counts the characters of the value , an escape is one character
+#[test]
+fn strings_of_at_most_1024_characters(){
+    let longest = "a".repeat(1024);
+    assert_eq!(strings(&format!("\"{}\"",longest)),vec![string(&longest,1)]);
+    assert_eq!(strings(&format!("\"{}\"",r"\n".repeat(1024))),vec![string(&"\n".repeat(1024),1)]);
+    assert_eq!(strings(&format!("\"{}\" x","a".repeat(1025))),vec![error("String constant too long",1),(OBJECTID,"x".to_string(),1)]);
+    let (_,diagnostics) = Scanner::new(format!("\"{}\"","a".repeat(1025))).scan_all();
+    assert_eq!(diagnostics.iter().map(|d| d.to_string()).collect::<Vec<String>>(),vec![
+        "1:1: error[E0005]: String constant is 1025 characters long , the limit is 1024"
+    ]);
+}
+
+#[test]
+fn integers_fit_in_an_int(){
+    let tokens = scan("2147483647 0 007");
+    assert_eq!(tokens.iter().map(|t| t.literal.clone()).collect::<Vec<Option<Literal>>>(),
+        vec![Some(Literal::INT(i32::MAX)),Some(Literal::INT(0)),Some(Literal::INT(7)),None]);
+    assert_eq!(tokens[2].lexeme,"007");
+    //~ is an operator , the literal of ~2147483648 is out of range too
+    let (tokens,diagnostics) = Scanner::new("x <- 2147483648;\ny <- ~99999999999999999999;".to_string()).scan_all();
+    assert_eq!(tokens.iter().map(|t| t.tokentype).collect::<Vec<TokenType>>(),
+        vec![OBJECTID,ASSIGN,ERROR,SEMICOLON,OBJECTID,ASSIGN,TILDA,ERROR,SEMICOLON,EOF]);
+    assert_eq!(tokens[2].string_value(),"Integer constant out of range");
+    assert_eq!(diagnostics.iter().map(|d| (d.to_string(),d.span)).collect::<Vec<(String,Span)>>(),vec![
+        ("1:6: error[E0006]: Integer constant 2147483648 does not fit in a 32 bit Int".to_string(),Span::new(5,15,1,6)),
+        ("2:7: error[E0006]: Integer constant 99999999999999999999 does not fit in a 32 bit Int".to_string(),Span::new(23,43,2,7))
+    ]);
+}
+
+//equal strings share one interned value , so later stages compare and store them cheaply
+#[test]
+fn typed_literals(){
+    let tokens = scan("\"same\" \"same\" true fALSE 12");
+    let (Some(Literal::STR(first)),Some(Literal::STR(second))) = (&tokens[0].literal,&tokens[1].literal) else{
+        panic!("two strings");
+    };
+    assert!(std::sync::Arc::ptr_eq(first,second));
+    assert_eq!((tokens[2].bool_value(),tokens[3].bool_value(),tokens[4].int_value()),(true,false,12));
+}
@@ -0,0 +1,56 @@
+use std::cell::Cell;
+
+use cool_compiler_rs::{Scanner,Parser,Token,TokenType};
+use cool_compiler_rs::astprinter::AstPrinter;
+
+const SOURCE:&str = "class Main inherits IO {
+    main() : Object { out_string(\"hi\\n\") };
+};";
+
+#[test]
+fn scanner_is_an_iterator(){
+    let streamed : Vec<Token> = Scanner::new(SOURCE.to_string()).collect();
+    let (scanned,_) = Scanner::new(SOURCE.to_string()).scan_all();
+    assert_eq!(streamed.len(),scanned.len());
+    assert_eq!(streamed.last().unwrap().tokentype,TokenType::EOF);
+    assert!(streamed.iter().zip(scanned.iter()).all(|(a,b)| a.lexeme == b.lexeme && a.span == b.span));
+}
+
+#[test]
+fn parser_reads_from_the_scanner(){
+    let mut scanner = Scanner::new(SOURCE.to_string());
+    let streamed = Parser::new(&mut scanner).parse_program().unwrap();
+    let tokens = Scanner::new(SOURCE.to_string()).scan_tokens().unwrap();
+    let collected = Parser::new(tokens).parse_program().unwrap();
+    assert_eq!(AstPrinter::print_program(&streamed),AstPrinter::print_program(&collected));
+}
+
+#[test]
+fn parser_pulls_one_token_ahead(){
+    //an endless stream , the parser must stop pulling once the expression is complete
+    let pulled = Cell::new(0);
+    let tokens = Scanner::new("x <- 1 + 2 ;".to_string())
+        .filter(|t| t.tokentype != TokenType::EOF)
+        .chain(std::iter::repeat_with(|| Scanner::new(";".to_string()).next().unwrap()))
+        .inspect(|_| pulled.set(pulled.get() + 1));
+    let expr = Parser::new(tokens).parse_expression().unwrap();
+    assert_eq!(expr.accept(&mut AstPrinter),"x <- (1 + 2)");
+    assert_eq!(pulled.get(),6);
+}
+
+#[test]
+fn lexical_errors_stay_with_the_scanner(){
+    let mut scanner = Scanner::new("class Main { x : Int <- 1 # ; };".to_string());
+    let program = Parser::new(&mut scanner).parse_program();
+    assert!(program.is_ok());
+    let diagnostics = scanner.take_diagnostics();
+    assert_eq!(diagnostics.len(),1);
+}
+
+#[test]
+fn stream_without_eof(){
+    let mut tokens = Scanner::new("class A { };".to_string()).scan_tokens().unwrap();
+    tokens.pop();
+    let program = Parser::new(tokens).parse_program().unwrap();
+    assert_eq!(program.classes().len(),1);
+}
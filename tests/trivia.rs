@@ -0,0 +1,51 @@
+mod common;
+
+use cool_compiler_rs::{Scanner,Token,TokenType,TriviaKind};
+use cool_compiler_rs::TriviaKind::*;
+
+fn scan(source:&str)->Vec<Token>{
+    Scanner::new(source.to_string()).with_trivia().collect()
+}
+
+fn kinds(trivia:&[cool_compiler_rs::Trivia])->Vec<TriviaKind>{
+    trivia.iter().map(|t| t.kind).collect()
+}
+
+//every .cl file shipped with the compiler
+fn sources()->Vec<(String,String)>{
+    common::sources(&["examples","tests/data"])
+}
+
+#[test]
+fn rebuilds_every_example(){
+    for (path,source) in sources(){
+        assert_eq!(Token::source_text(&scan(&source)),source,"{}",path);
+    }
+}
+
+#[test]
+fn rebuilds_around_lexical_errors(){
+    for source in ["x <- \"no end\ny", "# ~ é\r\n(* open", "\"ok\"", "", "  \n\t"]{
+        assert_eq!(Token::source_text(&scan(source)),source);
+    }
+}
+
+#[test]
+fn trivia_is_attached_by_line(){
+    let tokens = scan("-- header\nx <- 1; -- one\n  (* two *) y");
+    assert_eq!(kinds(&tokens[0].leading),vec![LINECOMMENT,NEWLINE]);
+    let semicolon = &tokens[3];
+    assert_eq!(semicolon.tokentype,TokenType::SEMICOLON);
+    assert_eq!(kinds(&semicolon.trailing),vec![WHITESPACE,LINECOMMENT]);
+    assert_eq!(semicolon.trailing[1].text,"-- one");
+    let y = &tokens[4];
+    assert_eq!(kinds(&y.leading),vec![NEWLINE,WHITESPACE,BLOCKCOMMENT,WHITESPACE]);
+    assert_eq!(y.leading[2].span.line,3);
+    assert_eq!(tokens[5].tokentype,TokenType::EOF);
+}
+
+#[test]
+fn off_by_default(){
+    let (tokens,_) = Scanner::new("x -- comment\n y".to_string()).scan_all();
+    assert!(tokens.iter().all(|t| t.leading.is_empty() && t.trailing.is_empty()));
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use cool_compiler_rs::astreader::AstReader;
+use cool_compiler_rs::semant;
+use common::semant_errors;
+
+/*
+the rules of the type checker , with the messages and lines of the reference coolc .
+every case is a small program that breaks one rule , A and B are on line 1 and 2 and the features
+under test start on line 4 in Main
+*/
+
+const CLASSES:&str = "\
+class A { f(x : Int, s : String) : Int { x }; g() : SELF_TYPE { self }; };
+class B inherits A {};
+class Main { main() : Int { 0 };
+";
+
+fn errors(features:&str)->Vec<String>{
+    semant_errors(&format!("{}{}}};\n",CLASSES,features))
+}
+
+fn none()->Vec<String>{
+    vec![]
+}
+
+#[test]
+fn well_typed_features(){
+    assert_eq!(errors("  a : A <- new B;\n  t() : Int { a.f(1, \"s\") };\n"),none());
+    assert_eq!(errors("  t() : Int { (new B)@A.f(1, \"s\") };\n"),none());
+    assert_eq!(errors("  t() : Object { case new B of a : A => a; o : Object => o; esac };\n"),none());
+}
+
+#[test]
+fn dispatch_arity(){
+    assert_eq!(errors("  t() : Object { (new A).f(1) };\n"),vec![
+        "test.cl:4: Method f called with wrong number of arguments."
+    ]);
+    assert_eq!(errors("  t() : Object { (new A).g(1, 2) };\n"),vec![
+        "test.cl:4: Method g called with wrong number of arguments."
+    ]);
+}
+
+#[test]
+fn dispatch_conformance(){
+    assert_eq!(errors("  t() : Object { (new A).f(1, 2) };\n"),vec![
+        "test.cl:4: In call of method f, type Int of parameter s does not conform to declared type String."
+    ]);
+    assert_eq!(errors("  t() : Object { (new A)@B.f(1, \"s\") };\n"),vec![
+        "test.cl:4: Expression type A does not conform to declared static dispatch type B."
+    ]);
+    assert_eq!(errors("  t() : Object { (new A)@C.f(1, \"s\") };\n"),vec![
+        "test.cl:4: Static dispatch to undefined class C."
+    ]);
+    assert_eq!(errors("  t() : Object { (new A).h() };\n"),vec![
+        "test.cl:4: Dispatch to undefined method h."
+    ]);
+}
+
+#[test]
+fn case_branches(){
+    assert_eq!(errors("  t() : Object { case 1 of\n    x : Int => 1;\n    y : Int => 2;\n  esac };\n"),vec![
+        "test.cl:6: Duplicate branch Int in case statement."
+    ]);
+    assert_eq!(errors("  t() : Object { case 1 of x : C => 1; esac };\n"),vec![
+        "test.cl:4: Class C of case branch is undefined."
+    ]);
+}
+
+#[test]
+fn self_type_misuse(){
+    assert_eq!(errors("  t(x : SELF_TYPE) : Object { x };\n"),vec![
+        "test.cl:4: Formal parameter x cannot have type SELF_TYPE."
+    ]);
+    assert_eq!(errors("  t() : Object { case 1 of x : SELF_TYPE => 1; esac };\n"),vec![
+        "test.cl:4: Identifier x declared with type SELF_TYPE in case branch."
+    ]);
+    assert_eq!(errors("  t() : Object { (new A)@SELF_TYPE.g() };\n"),vec![
+        "test.cl:4: Static dispatch to SELF_TYPE."
+    ]);
+    assert_eq!(errors("  t() : Object { let x : SELF_TYPE <- new Main in x };\n"),vec![
+        "test.cl:4: Inferred type Main of initialization of x does not conform to identifier's declared type SELF_TYPE."
+    ]);
+    assert_eq!(errors("  t() : A { new SELF_TYPE };\n"),vec![
+        "test.cl:4: Inferred return type SELF_TYPE of method t does not conform to declared return type A."
+    ]);
+    //SELF_TYPE used where it is allowed
+    assert_eq!(errors("  t() : SELF_TYPE { let x : SELF_TYPE <- self in x };\n  b : B <- (new B).g();\n"),none());
+}
+
+#[test]
+fn self_cannot_be_bound(){
+    assert_eq!(errors("  t() : Object { let self : Int <- 1 in self };\n"),vec![
+        "test.cl:4: 'self' cannot be bound in a 'let' expression."
+    ]);
+    assert_eq!(errors("  t(self : Int) : Object { 0 };\n"),vec![
+        "test.cl:4: 'self' cannot be the name of a formal parameter."
+    ]);
+    assert_eq!(errors("  t() : Object { case 1 of self : Int => 1; esac };\n"),vec![
+        "test.cl:4: 'self' bound in 'case'."
+    ]);
+    assert_eq!(errors("  t() : Object { self <- new Main };\n"),vec![
+        "test.cl:4: Cannot assign to 'self'."
+    ]);
+}
+
+//our parser already refuses an attribute named self , the reference parser leaves it to semant
+#[test]
+fn self_cannot_be_an_attribute(){
+    let dump = "\
+#1
+_program
+  #1
+  _class
+    Main
+    Object
+    \"test.cl\"
+    (
+    #2
+    _attr
+      self
+      Int
+      #2
+      _no_expr
+      : _no_type
+    #3
+    _method
+      main
+      Int
+      #3
+      _int
+        0
+      : _no_type
+    )
+";
+    let Err(errors) = semant::check(&AstReader::read_program(dump).unwrap()) else{
+        panic!("self is an attribute");
+    };
+    let reports : Vec<String> = errors.iter().map(|e| e.report()).collect();
+    assert_eq!(reports,vec!["test.cl:2: 'self' cannot be the name of an attribute."]);
+}
+
+#[test]
+fn attributes(){
+    assert_eq!(errors("  x : Int;\n  x : String;\n"),vec![
+        "test.cl:5: Attribute x is multiply defined in class."
+    ]);
+    assert_eq!(errors("  x : Int <- \"s\";\n"),vec![
+        "test.cl:4: Inferred type String of initialization of attribute x does not conform to declared type Int."
+    ]);
+    assert_eq!(semant_errors("class A { x : Int; };\nclass Main inherits A { x : Int; main() : Int { 0 }; };\n"),vec![
+        "test.cl:2: Attribute x is an attribute of an inherited class."
+    ]);
+}
+
+#[test]
+fn expressions(){
+    assert_eq!(errors("  t() : Object { 1 + true };\n"),vec!["test.cl:4: non-Int arguments: Int + Bool"]);
+    assert_eq!(errors("  t() : Object { 1 = \"s\" };\n"),vec!["test.cl:4: Illegal comparison with a basic type."]);
+    assert_eq!(errors("  t() : Object { if 1 then 2 else 3 fi };\n"),vec!["test.cl:4: Predicate of 'if' does not have type Bool."]);
+    assert_eq!(errors("  t() : Object { while 1 loop 2 pool };\n"),vec!["test.cl:4: Loop condition does not have type Bool."]);
+    assert_eq!(errors("  t() : Object { not 1 };\n"),vec!["test.cl:4: Argument of 'not' has type Int instead of Bool."]);
+    assert_eq!(errors("  t() : Object { ~true };\n"),vec!["test.cl:4: Argument of '~' has type Bool instead of Int."]);
+    assert_eq!(errors("  t() : Object { new C };\n"),vec!["test.cl:4: 'new' used with undefined class C."]);
+    assert_eq!(errors("  t() : Object { y };\n"),vec!["test.cl:4: Undeclared identifier y."]);
+    assert_eq!(errors("  t() : Object { y <- 1 };\n"),vec!["test.cl:4: Assignment to undeclared variable y."]);
+}
+
+//an expression over several lines is reported on the line it starts on , like the reference
+#[test]
+fn lines_of_expressions_over_several_lines(){
+    assert_eq!(errors("  t() : Int { {\n    true;\n  } };\n"),vec![
+        "test.cl:4: Inferred return type Bool of method t does not conform to declared return type Int."
+    ]);
+    assert_eq!(errors("  t() : Object { if {\n    1;\n  } then 2 else 3 fi };\n"),vec![
+        "test.cl:4: Predicate of 'if' does not have type Bool."
+    ]);
+    assert_eq!(errors("  t() : Object { while let x : Int in\n    x\n  loop 0 pool };\n"),vec![
+        "test.cl:4: Loop condition does not have type Bool."
+    ]);
+    assert_eq!(errors("  t() : Object { ~if true then\n    true else false fi };\n"),vec![
+        "test.cl:4: Argument of '~' has type Bool instead of Int."
+    ]);
+    assert_eq!(errors("  t() : Object { not case 1 of\n    x : Int => x; esac };\n"),vec![
+        "test.cl:4: Argument of 'not' has type Int instead of Bool."
+    ]);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use cool_compiler_rs::{Scanner,Parser,Token,TokenType,Diagnostics};
+use cool_compiler_rs::TokenType::*;
+use cool_compiler_rs::diagnostic::INVALID_CHARACTER;
+
+fn scan(source:&str)->(Vec<Token>,Diagnostics){
+    Scanner::new(source.to_string()).scan_all()
+}
+
+fn find<'a>(tokens:&'a [Token],tokentype:TokenType,lexeme:&str)->&'a Token{
+    tokens.iter().find(|t| t.tokentype == tokentype && t.lexeme == lexeme).unwrap()
+}
+
+//tests/data/greetings.cl is a made up fixture , not one of the examples . it puts multibyte characters
+//in a nested comment , a line comment and strings , the class starts on line 11
+#[test]
+fn accented_names_in_comments(){
+    let source = common::read("tests/data/greetings.cl");
+    let (tokens,diagnostics) = scan(&source);
+    assert!(diagnostics.is_empty());
+    let class = find(&tokens,KEYCLASS,"class");
+    assert_eq!((class.span.line,class.span.column),(11,1));
+    //the line comment above it holds multibyte characters
+    let greeting = find(&tokens,OBJECTID,"greeting");
+    assert_eq!((greeting.span.line,greeting.span.column),(13,5));
+    assert_eq!(&source[greeting.span.start..greeting.span.end],"greeting");
+    assert!(Parser::new(tokens).parse_program().is_ok());
+}
+
+#[test]
+fn strings_keep_their_characters(){
+    let source = common::read("tests/data/greetings.cl");
+    let (tokens,_) = scan(&source);
+    let strings : Vec<&str> = tokens.iter().filter(|t| t.tokentype == STRING).map(|t| t.string_value()).collect();
+    assert_eq!(strings,vec!["Grüße aus Köln","\n","¿Qué tal?\n","\n"]);
+    //columns after a string with multibyte characters count characters , not bytes
+    let hola = tokens.iter().find(|t| t.string_value() == "¿Qué tal?\n").unwrap();
+    let paren = tokens.iter().find(|t| t.tokentype == RIGHTPAREN && t.span.start > hola.span.start).unwrap();
+    assert_eq!(paren.span.column,hola.span.column + "\"¿Qué tal?\\n\"".chars().count());
+    assert_eq!(&source[paren.span.start..paren.span.end],")");
+}
+
+#[test]
+fn invalid_characters_outside_strings(){
+    let source = common::read("tests/data/invalid_characters.cl");
+    let (tokens,diagnostics) = scan(&source);
+    let errors : Vec<_> = diagnostics.iter().collect();
+    assert_eq!(errors.len(),2);
+    assert!(errors.iter().all(|d| d.code == INVALID_CHARACTER));
+
+    assert_eq!(errors[0].message,"Invalid character 'ï' (U+00EF)");
+    assert_eq!((errors[0].span.line,errors[0].span.column),(3,7));
+    assert_eq!(&source[errors[0].span.start..errors[0].span.end],"ï");
+
+    assert_eq!(errors[1].message,"Invalid character '×' (U+00D7)");
+    assert_eq!((errors[1].span.line,errors[1].span.column),(4,18));
+
+    //scanning goes on after each of them
+    let names : Vec<&str> = tokens.iter().filter(|t| t.tokentype == OBJECTID).map(|t| t.lexeme.as_str()).collect();
+    assert_eq!(names,vec!["na","ve","x"]);
+    assert_eq!(tokens.iter().filter(|t| t.tokentype == ERROR).count(),2);
+}
+
+#[test]
+fn control_characters_are_escaped_in_messages(){
+    let (tokens,diagnostics) = scan("x \u{7} y");
+    assert_eq!(tokens.len(),4);
+    assert_eq!(diagnostics.iter().next().unwrap().message,"Invalid character '\\u{7}' (U+0007)");
+}